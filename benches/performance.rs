@@ -0,0 +1,245 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    hash::BuildHasher,
+    hint::black_box,
+    rc::Rc,
+};
+
+use rust_thinking::{
+    bench::{Bencher, Group, Rng},
+    in_place::{compact_stable, dedup_by, partition_in_place, retain_by},
+    iterator_performance::{sum_for, sum_iter},
+};
+
+// 笔记中关于性能的说法，这里逐一用基准测试验证：
+// 1. 迭代器是零成本抽象，和下标循环一样快(learn_iterator / iterator_performance)
+// 2. Rc<RefCell<T>> 的开销很小，但并非为零(refcell)
+// 3. Cell 没有额外的性能损耗，和直接修改一致(refcell)
+// 4. HashMap 默认的 SipHash 比 ahash 慢(hash_map)
+// 5. 特征对象需要通过 vtable 动态分发，比静态分发(这里用枚举)慢(trait_obj)
+// 6. 基于 Cell::from_mut 的原地算法和 std 的 Vec::retain/dedup 在同一个量级(refcell / in_place)
+//
+// 运行方式: cargo bench [-- <过滤关键字>...]
+
+const LEN: usize = 64 * 1024;
+
+fn iterator(bencher: &Bencher, rng: &mut Rng) -> Group {
+    let samples = rng.f64_vec(LEN);
+    let mut group = Group::new("indexed loop vs iterator");
+    group.bench(bencher, "sum_for", || sum_for(black_box(&samples)));
+    group.bench(bencher, "sum_iter", || sum_iter(black_box(&samples)));
+    group
+}
+
+fn rc_refcell(bencher: &Bencher, rng: &mut Rng) -> Group {
+    let plain: Vec<u64> = (0..LEN).map(|_| rng.below(1000)).collect();
+    let shared: Vec<Rc<RefCell<u64>>> = plain.iter().map(|&v| Rc::new(RefCell::new(v))).collect();
+
+    let mut group = Group::new("Rc<RefCell<T>> overhead");
+    group.bench(bencher, "Vec<u64>", || {
+        plain.iter().fold(0u64, |acc, v| acc.wrapping_add(*v))
+    });
+    group.bench(bencher, "Vec<Rc<RefCell<u64>>> borrow", || {
+        shared
+            .iter()
+            .fold(0u64, |acc, v| acc.wrapping_add(*v.borrow()))
+    });
+    group.bench(bencher, "Vec<Rc<RefCell<u64>>> clone + borrow_mut", || {
+        for v in shared.iter() {
+            let owner = Rc::clone(v);
+            *owner.borrow_mut() += 1;
+        }
+    });
+    group
+}
+
+fn cell(bencher: &Bencher, rng: &mut Rng) -> Group {
+    let steps: Vec<u64> = (0..LEN).map(|_| rng.below(16)).collect();
+
+    let mut group = Group::new("Cell vs plain mutation");
+    group.bench(bencher, "&mut u64", || {
+        let mut x = 0u64;
+        let y = &mut x;
+        for s in steps.iter() {
+            *y = y.wrapping_add(*s);
+        }
+        x
+    });
+    group.bench(bencher, "Cell<u64>", || {
+        let x = Cell::new(0u64);
+        let (y, z) = (&x, &x);
+        for (i, s) in steps.iter().enumerate() {
+            let target = if i % 2 == 0 { y } else { z };
+            target.set(target.get().wrapping_add(*s));
+        }
+        x.get()
+    });
+    group
+}
+
+fn insert_and_lookup<S: BuildHasher + Default>(keys: &[u64]) -> u64 {
+    let mut map: HashMap<u64, u64, S> = HashMap::default();
+    for (i, k) in keys.iter().enumerate() {
+        map.insert(*k, i as u64);
+    }
+    keys.iter().filter_map(|k| map.get(k)).sum()
+}
+
+fn hashers(bencher: &Bencher, rng: &mut Rng) -> Group {
+    let keys: Vec<u64> = (0..LEN / 4).map(|_| rng.next_u64()).collect();
+
+    let mut group = Group::new("SipHash vs ahash (u64 keys)");
+    group.bench(bencher, "std RandomState (SipHash)", || {
+        insert_and_lookup::<std::collections::hash_map::RandomState>(&keys)
+    });
+    group.bench(bencher, "ahash::RandomState", || {
+        insert_and_lookup::<ahash::RandomState>(&keys)
+    });
+    group
+}
+
+trait Draw {
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+struct Circle(f64);
+
+impl Draw for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+impl Draw for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.0 * self.0
+    }
+}
+
+enum Shape {
+    Square(f64),
+    Circle(f64),
+}
+
+impl Shape {
+    fn area(&self) -> f64 {
+        match self {
+            Shape::Square(side) => side * side,
+            Shape::Circle(r) => std::f64::consts::PI * r * r,
+        }
+    }
+}
+
+fn dispatch(bencher: &Bencher, rng: &mut Rng) -> Group {
+    let specs: Vec<(bool, f64)> = (0..LEN)
+        .map(|_| (rng.below(2) == 0, rng.next_f64()))
+        .collect();
+    let objects: Vec<Box<dyn Draw>> = specs
+        .iter()
+        .map(|&(square, x)| -> Box<dyn Draw> {
+            if square {
+                Box::new(Square(x))
+            } else {
+                Box::new(Circle(x))
+            }
+        })
+        .collect();
+    let shapes: Vec<Shape> = specs
+        .iter()
+        .map(|&(square, x)| {
+            if square {
+                Shape::Square(x)
+            } else {
+                Shape::Circle(x)
+            }
+        })
+        .collect();
+
+    let mut group = Group::new("Box<dyn Draw> vs enum dispatch");
+    group.bench(bencher, "Vec<Box<dyn Draw>>", || {
+        objects.iter().map(|o| o.area()).sum::<f64>()
+    });
+    group.bench(bencher, "Vec<Shape> (enum)", || {
+        shapes.iter().map(|s| s.area()).sum::<f64>()
+    });
+    group
+}
+
+fn in_place(bencher: &Bencher, rng: &mut Rng) -> Group {
+    // 取值范围小，dedup 时才会有足够多的连续重复
+    let data: Vec<u32> = (0..LEN).map(|_| rng.below(4) as u32).collect();
+    // 每轮先把数据复制回缓冲区，缓冲区只分配一次
+    let mut buf = data.clone();
+
+    let mut group = Group::new("in_place (Cell::from_mut) vs std Vec");
+    group.bench(bencher, "Vec::retain", || {
+        buf.clear();
+        buf.extend_from_slice(&data);
+        buf.retain(|x| x % 2 == 0);
+        buf.len()
+    });
+    group.bench(bencher, "retain_by", || {
+        buf.clear();
+        buf.extend_from_slice(&data);
+        retain_by(&mut buf, |x| x % 2 == 0)
+    });
+    group.bench(bencher, "Vec::dedup", || {
+        buf.clear();
+        buf.extend_from_slice(&data);
+        buf.dedup();
+        buf.len()
+    });
+    group.bench(bencher, "dedup_by", || {
+        buf.clear();
+        buf.extend_from_slice(&data);
+        dedup_by(&mut buf, |a, b| a == b)
+    });
+    group.bench(bencher, "partition_in_place", || {
+        buf.clear();
+        buf.extend_from_slice(&data);
+        partition_in_place(&mut buf, |x| x % 2 == 0)
+    });
+    group.bench(bencher, "compact_stable", || {
+        buf.clear();
+        buf.extend_from_slice(&data);
+        compact_stable(&mut buf, |x| x % 2 == 0)
+    });
+    group.bench(bencher, "two filters into a new Vec", || {
+        let mut out: Vec<u32> = data.iter().copied().filter(|x| x % 2 == 0).collect();
+        let kept = out.len();
+        out.extend(data.iter().copied().filter(|x| x % 2 != 0));
+        black_box(out);
+        kept
+    });
+    group
+}
+
+type Suite = fn(&Bencher, &mut Rng) -> Group;
+
+fn main() {
+    // cargo bench 会额外传入 --bench 等参数，只把非 - 开头的参数当作过滤关键字
+    let filters: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|a| !a.starts_with('-'))
+        .collect();
+
+    let suites: &[(&str, Suite)] = &[
+        ("iterator", iterator),
+        ("rc_refcell", rc_refcell),
+        ("cell", cell),
+        ("hash", hashers),
+        ("dispatch", dispatch),
+        ("in_place", in_place),
+    ];
+
+    let bencher = Bencher::default();
+    for (name, suite) in suites {
+        if !filters.is_empty() && !filters.iter().any(|f| name.contains(f.as_str())) {
+            continue;
+        }
+        let mut rng = Rng::new(0x5EED);
+        println!("{}", suite(&bencher, &mut rng));
+    }
+}
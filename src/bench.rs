@@ -0,0 +1,212 @@
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+// 稳定版 Rust 上的基准测试工具
+// #[bench] 和 test::Bencher 需要 nightly 的 #![feature(test)]，这里用 Instant 自己实现：先预热估算单次耗时，
+// 再把若干次调用打包成一个样本，采集多个样本后统计中位数和 p95。配合 benches/ 下 harness = false 的基准测试使用。
+
+// 确定性的伪随机数生成器(SplitMix64)，同样的种子总是生成同样的序列，基准测试的输入因此可以复现，也不再需要 rand
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // [0, 1) 区间内均匀分布的浮点数，取高 53 位作为尾数
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // [0, bound) 区间内的整数，bound 必须大于 0
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "bound must be positive");
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+
+    pub fn f64_vec(&mut self, len: usize) -> Vec<f64> {
+        (0..len).map(|_| self.next_f64()).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub iters_per_sample: u64,
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    // 每个样本记录的是单次调用的平均耗时
+    pub fn from_samples(mut per_iter: Vec<Duration>, iters_per_sample: u64) -> Stats {
+        assert!(!per_iter.is_empty(), "no samples collected");
+        per_iter.sort();
+        let n = per_iter.len();
+        let total: Duration = per_iter.iter().sum();
+        Stats {
+            samples: n,
+            iters_per_sample,
+            min: per_iter[0],
+            median: percentile(&per_iter, 50),
+            p95: percentile(&per_iter, 95),
+            mean: total / n as u32,
+        }
+    }
+}
+
+// 最近秩法求百分位数，sorted 必须已经排好序
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+pub struct Bencher {
+    pub warmup: Duration,
+    pub sample_time: Duration,
+    pub samples: usize,
+}
+
+impl Default for Bencher {
+    fn default() -> Bencher {
+        Bencher {
+            warmup: Duration::from_millis(200),
+            sample_time: Duration::from_millis(5),
+            samples: 50,
+        }
+    }
+}
+
+impl Bencher {
+    pub fn run<R, F: FnMut() -> R>(&self, mut f: F) -> Stats {
+        // 预热：让缓存、分支预测器进入稳定状态，同时估算单次调用的耗时
+        let start = Instant::now();
+        let mut warmup_iters = 0u64;
+        while start.elapsed() < self.warmup || warmup_iters == 0 {
+            black_box(f());
+            warmup_iters += 1;
+        }
+        let per_iter = start.elapsed() / warmup_iters.min(u32::MAX as u64) as u32;
+
+        // 单次调用太快时计时器精度不够，把多次调用打包成一个样本
+        let iters_per_sample = if per_iter.is_zero() {
+            1000
+        } else {
+            (self.sample_time.as_nanos() / per_iter.as_nanos()).clamp(1, 1_000_000) as u64
+        };
+
+        let per_iter: Vec<Duration> = (0..self.samples.max(1))
+            .map(|_| {
+                let start = Instant::now();
+                for _ in 0..iters_per_sample {
+                    black_box(f());
+                }
+                start.elapsed() / iters_per_sample as u32
+            })
+            .collect();
+        Stats::from_samples(per_iter, iters_per_sample)
+    }
+}
+
+// 一组互相对比的基准测试，例如 "for 循环 vs 迭代器"
+pub struct Group {
+    pub name: &'static str,
+    pub results: Vec<(&'static str, Stats)>,
+}
+
+impl Group {
+    pub fn new(name: &'static str) -> Group {
+        Group {
+            name,
+            results: Vec::new(),
+        }
+    }
+
+    pub fn bench<R, F: FnMut() -> R>(&mut self, bencher: &Bencher, name: &'static str, f: F) {
+        self.results.push((name, bencher.run(f)));
+    }
+}
+
+fn fmt_duration(d: Duration) -> String {
+    let nanos = d.as_secs_f64() * 1e9;
+    if nanos < 1e3 {
+        format!("{:.1} ns", nanos)
+    } else if nanos < 1e6 {
+        format!("{:.2} µs", nanos / 1e3)
+    } else {
+        format!("{:.2} ms", nanos / 1e6)
+    }
+}
+
+// 以组内最快的中位数为基准，输出每个用例的 median / p95 / mean 以及相对倍数
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.name)?;
+        let fastest = self.results.iter().map(|(_, s)| s.median).min();
+        let width = self.results.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
+        for (name, stats) in &self.results {
+            let ratio = match fastest {
+                Some(fastest) if !fastest.is_zero() => {
+                    stats.median.as_secs_f64() / fastest.as_secs_f64()
+                }
+                _ => 1.0,
+            };
+            writeln!(
+                f,
+                "  {:<width$}  median {:>10}  p95 {:>10}  mean {:>10}  x{:.2}",
+                name,
+                fmt_duration(stats.median),
+                fmt_duration(stats.p95),
+                fmt_duration(stats.mean),
+                ratio,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_deterministic() {
+        let a: Vec<u64> = {
+            let mut rng = Rng::new(42);
+            (0..8).map(|_| rng.next_u64()).collect()
+        };
+        let mut rng = Rng::new(42);
+        assert!(a.iter().all(|&x| x == rng.next_u64()));
+
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let f = rng.next_f64();
+            assert!((0.0..1.0).contains(&f));
+            assert!(rng.below(10) < 10);
+        }
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let samples: Vec<Duration> = (1..=100).rev().map(Duration::from_nanos).collect();
+        let stats = Stats::from_samples(samples, 1);
+        assert_eq!(stats.min, Duration::from_nanos(1));
+        assert_eq!(stats.median, Duration::from_nanos(50));
+        assert_eq!(stats.p95, Duration::from_nanos(95));
+        assert_eq!(stats.mean, Duration::from_nanos(50));
+    }
+}
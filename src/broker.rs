@@ -0,0 +1,553 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use crate::refcell::Messenger;
+
+// 按主题分发消息的进程内消息代理
+// refcell 章节的 MsgQueue 只会往 RefCell<Vec<String>> 里追加消息，没有人读取。这里把它扩展成一个消息代理：
+// 1. 消息发布到命名的主题(topic)上，主题在第一次使用时自动创建
+// 2. 订阅者是装箱的闭包，每条被接受的消息都会立即推送给该主题的所有订阅者，subscribe 返回的 Subscription 用来取消订阅
+// 3. 每个主题还有一个有界队列，保存被接受的消息，供 drain 按顺序取走；队列满时按 Overflow 策略丢弃最旧的消息或者拒绝新消息
+// 同一套 API 有两个版本，都实现了 Broker 特征：
+// 1. LocalBroker：状态放在 Rc<RefCell<_>> 中，只能在单线程中使用，订阅者闭包可以捕获 Rc 等非 Send 的值
+// 2. SyncBroker：状态放在 Arc<Mutex<_>> 中，可以克隆到多个线程中同时发布和消费，订阅者闭包必须是 Send 的
+// 两个版本共用下面的 Topics，区别只在于用什么把它包起来，这正是 refcell 和 rc_arc 章节中对比的两种组合。
+
+/// 队列满时的处理策略。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    /// 丢弃队列中最旧的消息，为新消息腾出位置。
+    #[default]
+    DropOldest,
+    /// 拒绝新消息，订阅者也不会收到它。
+    Reject,
+}
+
+/// 发布的结果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Publish<M> {
+    /// 消息进入了队列。
+    Queued,
+    /// 消息进入了队列，但挤掉了队列中最旧的消息。
+    DroppedOldest(M),
+    /// 队列已满，消息被拒绝，原样返回。
+    Rejected(M),
+}
+
+impl<M> Publish<M> {
+    // 除了 Rejected 之外，消息都已经推送给了订阅者
+    pub fn is_accepted(&self) -> bool {
+        !matches!(self, Publish::Rejected(_))
+    }
+}
+
+/// 订阅凭证，交给 [`Broker::unsubscribe`] 取消订阅。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Subscription {
+    topic: String,
+    id: u64,
+}
+
+impl Subscription {
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+}
+
+/// 消息代理的公共接口，由 [`LocalBroker`] 和 [`SyncBroker`] 实现。
+///
+/// ```
+/// use std::{cell::RefCell, rc::Rc};
+/// use rust_thinking::broker::{Broker, LocalBroker, Overflow, Publish};
+///
+/// let broker = LocalBroker::new(2, Overflow::DropOldest);
+/// let seen = Rc::new(RefCell::new(Vec::new()));
+/// let log = seen.clone();
+/// let sub = broker.subscribe("orders", Box::new(move |m: &u32| log.borrow_mut().push(*m)));
+///
+/// broker.publish("orders", 1);
+/// broker.publish("orders", 2);
+/// assert_eq!(broker.publish("orders", 3), Publish::DroppedOldest(1));
+/// assert!(broker.unsubscribe(&sub));
+/// broker.publish("orders", 4);
+///
+/// assert_eq!(*seen.borrow(), [1, 2, 3]);
+/// assert_eq!(broker.drain("orders"), [3, 4]);
+/// assert!(broker.drain("orders").is_empty());
+/// ```
+pub trait Broker<M> {
+    /// 装箱的订阅者闭包，线程安全的版本要求它是 `Send` 的。
+    type Subscriber;
+
+    fn subscribe(&self, topic: &str, subscriber: Self::Subscriber) -> Subscription;
+
+    /// 取消订阅，订阅已经取消过时返回 `false`。
+    fn unsubscribe(&self, subscription: &Subscription) -> bool;
+
+    /// 把消息放进主题的队列并推送给所有订阅者。
+    fn publish(&self, topic: &str, msg: M) -> Publish<M>;
+
+    /// 按发布顺序取走主题队列中的所有消息。
+    fn drain(&self, topic: &str) -> Vec<M>;
+
+    /// 主题队列中等待 `drain` 的消息数。
+    fn pending(&self, topic: &str) -> usize;
+
+    /// 所有主题的名字，按字典序排列。
+    fn topics(&self) -> Vec<String>;
+}
+
+/// 把 [`Broker`] 的一个主题包装成 [`Messenger`]，原来给 `MsgQueue` 发消息的代码不用修改。
+///
+/// `Messenger::send` 没有返回值，所以 `Channel` 是有损的：`Overflow::Reject` 时被拒绝的消息只计入 [`rejected`](Channel::rejected)，
+/// `Overflow::DropOldest` 时被挤掉的是队列中更早的消息。需要逐条处理发布结果时直接调用 [`Broker::publish`]。
+///
+/// ```
+/// use rust_thinking::{
+///     broker::{Broker, Channel, LocalBroker, Overflow},
+///     refcell::Messenger,
+/// };
+///
+/// let broker = LocalBroker::new(8, Overflow::Reject);
+/// let alerts = Channel::new(&broker, "alerts");
+/// alerts.send("disk full".to_string());
+/// assert_eq!(broker.drain("alerts"), ["disk full"]);
+/// assert_eq!(alerts.rejected(), 0);
+/// ```
+pub struct Channel<'b, B> {
+    broker: &'b B,
+    topic: String,
+    rejected: Cell<usize>,
+}
+
+impl<'b, B> Channel<'b, B> {
+    pub fn new(broker: &'b B, topic: &str) -> Channel<'b, B> {
+        Channel {
+            broker,
+            topic: topic.to_string(),
+            rejected: Cell::new(0),
+        }
+    }
+
+    /// 通过这个 `Channel` 发送、但被队列拒绝的消息数。
+    pub fn rejected(&self) -> usize {
+        self.rejected.get()
+    }
+}
+
+impl<B: Broker<String>> Messenger for Channel<'_, B> {
+    fn send(&self, msg: String) {
+        if !self.broker.publish(&self.topic, msg).is_accepted() {
+            self.rejected.set(self.rejected.get() + 1);
+        }
+    }
+}
+
+struct Topic<M, H> {
+    queue: VecDeque<M>,
+    subscribers: Vec<(u64, H)>,
+}
+
+// 两个版本共用的状态，H 是订阅者在各自版本中的共享指针
+struct Topics<M, H> {
+    topics: HashMap<String, Topic<M, H>>,
+    capacity: usize,
+    overflow: Overflow,
+    next_id: u64,
+}
+
+impl<M: Clone, H: Clone> Topics<M, H> {
+    fn new(capacity: usize, overflow: Overflow) -> Topics<M, H> {
+        assert!(capacity > 0, "capacity must be positive");
+        Topics {
+            topics: HashMap::new(),
+            capacity,
+            overflow,
+            next_id: 0,
+        }
+    }
+
+    fn topic(&mut self, name: &str) -> &mut Topic<M, H> {
+        self.topics
+            .entry(name.to_string())
+            .or_insert_with(|| Topic {
+                queue: VecDeque::new(),
+                subscribers: Vec::new(),
+            })
+    }
+
+    fn subscribe(&mut self, name: &str, subscriber: H) -> Subscription {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.topic(name).subscribers.push((id, subscriber));
+        Subscription {
+            topic: name.to_string(),
+            id,
+        }
+    }
+
+    fn unsubscribe(&mut self, subscription: &Subscription) -> bool {
+        let Some(topic) = self.topics.get_mut(&subscription.topic) else {
+            return false;
+        };
+        let before = topic.subscribers.len();
+        topic.subscribers.retain(|(id, _)| *id != subscription.id);
+        topic.subscribers.len() != before
+    }
+
+    // 入队，并返回需要推送的订阅者；推送在释放状态的借用(锁)之后进行，订阅者里可以再调用 Broker 的方法
+    fn enqueue(&mut self, name: &str, msg: M) -> (Publish<M>, Vec<H>) {
+        let (capacity, overflow) = (self.capacity, self.overflow);
+        let topic = self.topic(name);
+        let outcome = if topic.queue.len() < capacity {
+            Publish::Queued
+        } else {
+            match overflow {
+                Overflow::Reject => return (Publish::Rejected(msg), Vec::new()),
+                Overflow::DropOldest => Publish::DroppedOldest(topic.queue.pop_front().unwrap()),
+            }
+        };
+        topic.queue.push_back(msg);
+        let subscribers = topic.subscribers.iter().map(|(_, h)| h.clone()).collect();
+        (outcome, subscribers)
+    }
+
+    fn drain(&mut self, name: &str) -> Vec<M> {
+        self.topics
+            .get_mut(name)
+            .map(|topic| topic.queue.drain(..).collect())
+            .unwrap_or_default()
+    }
+
+    fn pending(&self, name: &str) -> usize {
+        self.topics.get(name).map_or(0, |topic| topic.queue.len())
+    }
+
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.topics.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+type LocalSubscriber<M> = Rc<RefCell<Box<dyn FnMut(&M)>>>;
+
+/// 单线程版本的消息代理，克隆出来的句柄共享同一份状态。
+///
+/// 推送时不持有状态的借用，订阅者在回调中可以调用这个代理的任意方法，包括向其它主题发布消息。
+/// 但订阅者在推送期间被可变借用，如果它(直接或者经由其它订阅者转发)向自己订阅的主题发布消息，会再次推送给自己，此时 panic。
+pub struct LocalBroker<M> {
+    state: Rc<RefCell<Topics<M, LocalSubscriber<M>>>>,
+}
+
+impl<M> Clone for LocalBroker<M> {
+    fn clone(&self) -> LocalBroker<M> {
+        LocalBroker {
+            state: Rc::clone(&self.state),
+        }
+    }
+}
+
+impl<M: Clone> LocalBroker<M> {
+    // 每个主题的队列最多保存 capacity 条消息
+    pub fn new(capacity: usize, overflow: Overflow) -> LocalBroker<M> {
+        LocalBroker {
+            state: Rc::new(RefCell::new(Topics::new(capacity, overflow))),
+        }
+    }
+}
+
+impl<M: Clone> Broker<M> for LocalBroker<M> {
+    type Subscriber = Box<dyn FnMut(&M)>;
+
+    fn subscribe(&self, topic: &str, subscriber: Box<dyn FnMut(&M)>) -> Subscription {
+        self.state
+            .borrow_mut()
+            .subscribe(topic, Rc::new(RefCell::new(subscriber)))
+    }
+
+    fn unsubscribe(&self, subscription: &Subscription) -> bool {
+        self.state.borrow_mut().unsubscribe(subscription)
+    }
+
+    fn publish(&self, topic: &str, msg: M) -> Publish<M> {
+        let (outcome, subscribers) = self.state.borrow_mut().enqueue(topic, msg.clone());
+        for subscriber in subscribers {
+            let mut subscriber = subscriber
+                .try_borrow_mut()
+                .expect("subscriber published to a topic it is subscribed to");
+            subscriber(&msg);
+        }
+        outcome
+    }
+
+    fn drain(&self, topic: &str) -> Vec<M> {
+        self.state.borrow_mut().drain(topic)
+    }
+
+    fn pending(&self, topic: &str) -> usize {
+        self.state.borrow().pending(topic)
+    }
+
+    fn topics(&self) -> Vec<String> {
+        self.state.borrow().names()
+    }
+}
+
+type SyncSubscriber<M> = Arc<Mutex<Box<dyn FnMut(&M) + Send>>>;
+
+/// 线程安全版本的消息代理，克隆出来的句柄可以发送到其它线程。
+///
+/// 每个订阅者有自己的锁，同一个订阅者同一时间只会被一个线程调用；推送时不持有状态的锁，订阅者在回调中可以调用这个代理的任意方法。
+/// 只有以下两种情况会死锁：
+/// 1. 订阅者(直接或者经由其它订阅者转发)向自己订阅的主题发布消息，线程会等待自己已经持有的锁
+/// 2. 订阅者之间的转发形成环，例如 A 转发给 B 订阅的主题、B 又转发给 A 订阅的主题，两个线程分别从 A、B 开始推送时会互相等待
+pub struct SyncBroker<M> {
+    state: Arc<Mutex<Topics<M, SyncSubscriber<M>>>>,
+}
+
+impl<M> Clone for SyncBroker<M> {
+    fn clone(&self) -> SyncBroker<M> {
+        SyncBroker {
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl<M: Clone> SyncBroker<M> {
+    pub fn new(capacity: usize, overflow: Overflow) -> SyncBroker<M> {
+        SyncBroker {
+            state: Arc::new(Mutex::new(Topics::new(capacity, overflow))),
+        }
+    }
+}
+
+impl<M: Clone> Broker<M> for SyncBroker<M> {
+    type Subscriber = Box<dyn FnMut(&M) + Send>;
+
+    fn subscribe(&self, topic: &str, subscriber: Box<dyn FnMut(&M) + Send>) -> Subscription {
+        self.state
+            .lock()
+            .unwrap()
+            .subscribe(topic, Arc::new(Mutex::new(subscriber)))
+    }
+
+    fn unsubscribe(&self, subscription: &Subscription) -> bool {
+        self.state.lock().unwrap().unsubscribe(subscription)
+    }
+
+    fn publish(&self, topic: &str, msg: M) -> Publish<M> {
+        let (outcome, subscribers) = self.state.lock().unwrap().enqueue(topic, msg.clone());
+        for subscriber in subscribers {
+            (subscriber.lock().unwrap())(&msg);
+        }
+        outcome
+    }
+
+    fn drain(&self, topic: &str) -> Vec<M> {
+        self.state.lock().unwrap().drain(topic)
+    }
+
+    fn pending(&self, topic: &str) -> usize {
+        self.state.lock().unwrap().pending(topic)
+    }
+
+    fn topics(&self) -> Vec<String> {
+        self.state.lock().unwrap().names()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{sync::mpsc, thread};
+
+    // 对两个版本运行同一组检查，只依赖 Broker 特征
+    fn check_queue_policies<B: Broker<u32>>(make: impl Fn(usize, Overflow) -> B) {
+        let broker = make(3, Overflow::DropOldest);
+        for i in 0..3 {
+            assert_eq!(broker.publish("t", i), Publish::Queued);
+        }
+        assert_eq!(broker.publish("t", 3), Publish::DroppedOldest(0));
+        assert_eq!(broker.pending("t"), 3);
+        assert_eq!(broker.drain("t"), [1, 2, 3]);
+        assert_eq!(broker.pending("t"), 0);
+
+        let broker = make(2, Overflow::Reject);
+        assert!(broker.publish("t", 0).is_accepted());
+        assert!(broker.publish("t", 1).is_accepted());
+        assert_eq!(broker.publish("t", 2), Publish::Rejected(2));
+        assert_eq!(broker.drain("t"), [0, 1]);
+        assert_eq!(broker.publish("t", 3), Publish::Queued);
+
+        // 主题之间互不影响
+        assert_eq!(broker.publish("other", 9), Publish::Queued);
+        assert_eq!(broker.drain("t"), [3]);
+        assert_eq!(broker.drain("other"), [9]);
+        assert!(broker.drain("missing").is_empty());
+        assert_eq!(broker.topics(), ["other", "t"]);
+    }
+
+    #[test]
+    fn both_editions_share_queue_semantics() {
+        check_queue_policies(LocalBroker::new);
+        check_queue_policies(SyncBroker::new);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity must be positive")]
+    fn zero_capacity_panics() {
+        LocalBroker::<u32>::new(0, Overflow::Reject);
+    }
+
+    #[test]
+    fn local_subscribers_and_unsubscribe() {
+        let broker = LocalBroker::new(1, Overflow::Reject);
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        let log = seen.clone();
+        let a = broker.subscribe(
+            "news",
+            Box::new(move |m: &String| log.borrow_mut().push(format!("a:{m}"))),
+        );
+        let log = seen.clone();
+        let b = broker.subscribe(
+            "news",
+            Box::new(move |m: &String| log.borrow_mut().push(format!("b:{m}"))),
+        );
+        assert_eq!(a.topic(), "news");
+        assert_ne!(a, b);
+
+        broker.publish("news", "1".to_string());
+        // 被拒绝的消息不会推送给订阅者
+        assert!(!broker.publish("news", "2".to_string()).is_accepted());
+        assert!(broker.unsubscribe(&a));
+        assert!(!broker.unsubscribe(&a));
+        broker.drain("news");
+        broker.publish("news", "3".to_string());
+        assert_eq!(*seen.borrow(), ["a:1", "b:1", "b:3"]);
+    }
+
+    #[test]
+    fn subscribers_can_republish_to_other_topics() {
+        // 推送发生在释放状态借用之后，订阅者可以转发到其它主题
+        let broker = LocalBroker::new(4, Overflow::DropOldest);
+        let forward = broker.clone();
+        broker.subscribe(
+            "raw",
+            Box::new(move |m: &i32| {
+                forward.publish("doubled", m * 2);
+            }),
+        );
+        broker.publish("raw", 1);
+        broker.publish("raw", 5);
+        assert_eq!(broker.drain("doubled"), [2, 10]);
+    }
+
+    #[test]
+    #[should_panic(expected = "subscriber published to a topic it is subscribed to")]
+    fn local_subscriber_publishing_to_its_own_topic_panics() {
+        let broker = LocalBroker::new(4, Overflow::DropOldest);
+        let echo = broker.clone();
+        broker.subscribe(
+            "echo",
+            Box::new(move |m: &i32| {
+                echo.publish("echo", m + 1);
+            }),
+        );
+        broker.publish("echo", 1);
+    }
+
+    #[test]
+    fn sync_subscribers_can_use_the_broker_without_deadlock() {
+        // 转发链 a -> b -> c 没有环，订阅者还在回调中查询、取走自己主题的队列
+        let broker = SyncBroker::new(8, Overflow::DropOldest);
+        let forward = broker.clone();
+        broker.subscribe(
+            "a",
+            Box::new(move |m: &u32| {
+                forward.pending("a");
+                forward.drain("a");
+                forward.publish("b", m + 1);
+            }),
+        );
+        let forward = broker.clone();
+        broker.subscribe(
+            "b",
+            Box::new(move |m: &u32| {
+                forward.publish("c", m * 10);
+            }),
+        );
+
+        let workers: Vec<_> = (0..4)
+            .map(|i| {
+                let broker = broker.clone();
+                thread::spawn(move || broker.publish("a", i))
+            })
+            .collect();
+        for worker in workers {
+            assert!(worker.join().unwrap().is_accepted());
+        }
+        let mut c = broker.drain("c");
+        c.sort();
+        assert_eq!(c, [10, 20, 30, 40]);
+        assert_eq!(broker.pending("b"), 4);
+    }
+
+    #[test]
+    fn channel_is_a_messenger() {
+        let broker = SyncBroker::new(4, Overflow::Reject);
+        let channel = Channel::new(&broker, "log");
+        channel.send("hello".to_string());
+        channel.send("world".to_string());
+        assert_eq!(broker.drain("log"), ["hello", "world"]);
+    }
+
+    #[test]
+    fn channel_counts_rejected_messages() {
+        let broker = LocalBroker::new(2, Overflow::Reject);
+        let channel = Channel::new(&broker, "log");
+        for word in ["a", "b", "c", "d"] {
+            channel.send(word.to_string());
+        }
+        assert_eq!(channel.rejected(), 2);
+        assert_eq!(broker.drain("log"), ["a", "b"]);
+        channel.send("e".to_string());
+        assert_eq!(channel.rejected(), 2);
+    }
+
+    #[test]
+    fn sync_broker_across_threads() {
+        let broker = SyncBroker::new(1000, Overflow::Reject);
+        let (tx, rx) = mpsc::channel();
+        broker.subscribe("jobs", Box::new(move |m: &u32| tx.send(*m).unwrap()));
+
+        let producers: Vec<_> = (0..4)
+            .map(|p| {
+                let broker = broker.clone();
+                thread::spawn(move || {
+                    for i in 0..50 {
+                        assert!(broker.publish("jobs", p * 100 + i).is_accepted());
+                    }
+                })
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        let mut drained = broker.drain("jobs");
+        drained.sort();
+        let mut received: Vec<u32> = rx.try_iter().collect();
+        received.sort();
+        let expected: Vec<u32> = (0..4)
+            .flat_map(|p| (0..50).map(move |i| p * 100 + i))
+            .collect();
+        assert_eq!(drained, expected);
+        assert_eq!(received, expected);
+    }
+}
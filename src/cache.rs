@@ -0,0 +1,439 @@
+use std::{
+    collections::{hash_map::RandomState, BTreeMap, HashMap},
+    hash::BuildHasher,
+};
+
+use crate::{deep_trait::CacheableItem, memo::MemoStats};
+
+// 基于 CacheableItem 的缓存
+// deep_trait 章节中的 CacheableItem 只声明了关联类型 Address 和 is_null，这里用它实现一个真正的缓存：
+// 1. Address 只要求 AsRef<[u8]> + Eq，没有要求 Hash，所以按地址的字节计算哈希，哈希相同的条目放在同一个桶里再用 Eq 区分
+// 2. is_null 为 true 的条目不会被放进缓存
+// 3. 容量有上限，超出时按 LRU(最久未使用)或 FIFO(最先放入)淘汰
+// 命中统计沿用 memo 模块的 MemoStats。
+
+/// 超出容量时的淘汰策略。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eviction {
+    // 淘汰最久没有被 insert 或 get 访问过的条目
+    Lru,
+    // 淘汰最先放入的条目，get 和覆盖写入都不会改变顺序
+    Fifo,
+}
+
+/// `Cache::insert` 的结果。
+#[derive(Debug, PartialEq)]
+pub enum Insert<I: CacheableItem> {
+    Stored,
+    // 地址已存在，返回被覆盖的旧条目
+    Replaced(I),
+    // 为了腾出空间淘汰了一个条目
+    Evicted(I::Address, I),
+    // 空条目，原样返回
+    Rejected(I),
+}
+
+struct Slot<I: CacheableItem> {
+    address: I::Address,
+    item: I,
+    tick: u64,
+}
+
+/// 以 `I::Address` 为键的缓存，容量固定，超出时按 [`Eviction`] 策略淘汰。
+///
+/// ```
+/// use rust_thinking::cache::{Cache, Eviction, Insert, Page};
+///
+/// let mut cache = Cache::new(2, Eviction::Lru);
+/// cache.insert("/".to_string(), Page::new("<h1>home</h1>"));
+/// cache.insert("/about".to_string(), Page::new("<p>about</p>"));
+/// assert!(cache.get(&"/".to_string()).is_some());
+///
+/// // "/about" 最久没有被访问，被淘汰
+/// let evicted = cache.insert("/blog".to_string(), Page::new("<ul></ul>"));
+/// assert_eq!(evicted, Insert::Evicted("/about".to_string(), Page::new("<p>about</p>")));
+///
+/// // 空页面不会被缓存
+/// assert_eq!(cache.insert("/404".to_string(), Page::default()), Insert::Rejected(Page::default()));
+/// assert_eq!(cache.len(), 2);
+/// ```
+pub struct Cache<I: CacheableItem, S = RandomState> {
+    buckets: HashMap<u64, Vec<Slot<I>>>,
+    // 访问序号 -> (哈希, 地址)，序号最小的就是下一个被淘汰的条目
+    order: BTreeMap<u64, (u64, I::Address)>,
+    tick: u64,
+    len: usize,
+    capacity: usize,
+    policy: Eviction,
+    hasher: S,
+    stats: MemoStats,
+}
+
+impl<I: CacheableItem> Cache<I> {
+    pub fn new(capacity: usize, policy: Eviction) -> Cache<I> {
+        Cache::with_hasher(capacity, policy, RandomState::new())
+    }
+}
+
+impl<I: CacheableItem, S: BuildHasher> Cache<I, S> {
+    pub fn with_hasher(capacity: usize, policy: Eviction, hasher: S) -> Cache<I, S> {
+        assert!(capacity > 0, "capacity must be positive");
+        Cache {
+            buckets: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            len: 0,
+            capacity,
+            policy,
+            hasher,
+            stats: MemoStats::default(),
+        }
+    }
+
+    fn hash(&self, address: &I::Address) -> u64 {
+        self.hasher.hash_one(address.as_ref())
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn slot_mut(&mut self, hash: u64, address: &I::Address) -> Option<&mut Slot<I>> {
+        self.buckets
+            .get_mut(&hash)?
+            .iter_mut()
+            .find(|slot| slot.address == *address)
+    }
+
+    fn take(&mut self, hash: u64, address: &I::Address) -> Option<Slot<I>> {
+        let bucket = self.buckets.get_mut(&hash)?;
+        let index = bucket.iter().position(|slot| slot.address == *address)?;
+        let slot = bucket.swap_remove(index);
+        if bucket.is_empty() {
+            self.buckets.remove(&hash);
+        }
+        self.order.remove(&slot.tick);
+        self.len -= 1;
+        Some(slot)
+    }
+
+    pub fn insert(&mut self, address: I::Address, item: I) -> Insert<I> {
+        if item.is_null() {
+            return Insert::Rejected(item);
+        }
+        let hash = self.hash(&address);
+        let tick = self.next_tick();
+        let policy = self.policy;
+        if let Some(slot) = self.slot_mut(hash, &address) {
+            let old = std::mem::replace(&mut slot.item, item);
+            if policy == Eviction::Lru {
+                let last = std::mem::replace(&mut slot.tick, tick);
+                let entry = self.order.remove(&last).expect("order out of sync");
+                self.order.insert(tick, entry);
+            }
+            return Insert::Replaced(old);
+        }
+
+        let mut result = Insert::Stored;
+        if self.len == self.capacity {
+            let (_, (oldest_hash, oldest)) = self.order.pop_first().expect("order out of sync");
+            let slot = self.take(oldest_hash, &oldest).expect("order out of sync");
+            self.stats.evictions += 1;
+            result = Insert::Evicted(slot.address, slot.item);
+        }
+        self.order.insert(tick, (hash, address.clone()));
+        self.buckets.entry(hash).or_default().push(Slot {
+            address,
+            item,
+            tick,
+        });
+        self.len += 1;
+        result
+    }
+
+    // 按淘汰策略记录一次访问，LRU 下会把条目标记为最近使用
+    pub fn get(&mut self, address: &I::Address) -> Option<&I> {
+        let hash = self.hash(address);
+        let tick = self.next_tick();
+        let policy = self.policy;
+        let last = match self.slot_mut(hash, address) {
+            Some(slot) if policy == Eviction::Lru => Some(std::mem::replace(&mut slot.tick, tick)),
+            Some(_) => None,
+            None => {
+                self.stats.misses += 1;
+                return None;
+            }
+        };
+        self.stats.hits += 1;
+        if let Some(last) = last {
+            let entry = self.order.remove(&last).expect("order out of sync");
+            self.order.insert(tick, entry);
+        }
+        self.peek(address)
+    }
+
+    // 只读访问，不影响淘汰顺序和命中统计
+    pub fn peek(&self, address: &I::Address) -> Option<&I> {
+        self.buckets
+            .get(&self.hash(address))?
+            .iter()
+            .find(|slot| slot.address == *address)
+            .map(|slot| &slot.item)
+    }
+
+    pub fn contains(&self, address: &I::Address) -> bool {
+        self.peek(address).is_some()
+    }
+
+    pub fn remove(&mut self, address: &I::Address) -> Option<I> {
+        let hash = self.hash(address);
+        self.take(hash, address).map(|slot| slot.item)
+    }
+
+    // 按淘汰顺序列出地址，第一个就是下一个会被淘汰的条目
+    pub fn addresses(&self) -> Vec<I::Address> {
+        self.order
+            .values()
+            .map(|(_, address)| address.clone())
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.order.clear();
+        self.len = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> Eviction {
+        self.policy
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+}
+
+// 下面是三种地址类型的示例条目
+
+/// 以 URL 字符串为地址的网页，内容为空时视为空条目。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Page {
+    pub html: String,
+}
+
+impl Page {
+    pub fn new(html: &str) -> Page {
+        Page {
+            html: html.to_string(),
+        }
+    }
+}
+
+impl CacheableItem for Page {
+    type Address = String;
+
+    fn is_null(&self) -> bool {
+        self.html.is_empty()
+    }
+}
+
+/// 以 4 字节 IPv4 地址为键的主机信息，没有主机名时视为空条目。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Host {
+    pub name: String,
+    pub latency_ms: u32,
+}
+
+impl CacheableItem for Host {
+    type Address = [u8; 4];
+
+    fn is_null(&self) -> bool {
+        self.name.is_empty()
+    }
+}
+
+/// 数值 id，按大端序保存成字节，满足 `AsRef<[u8]>` 的要求。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordId([u8; 8]);
+
+impl From<u64> for RecordId {
+    fn from(id: u64) -> RecordId {
+        RecordId(id.to_be_bytes())
+    }
+}
+
+impl From<RecordId> for u64 {
+    fn from(id: RecordId) -> u64 {
+        u64::from_be_bytes(id.0)
+    }
+}
+
+impl AsRef<[u8]> for RecordId {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// 以数值 id 为地址的数据库记录，被标记删除的记录视为空条目。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    pub balance: i64,
+    pub deleted: bool,
+}
+
+impl CacheableItem for Record {
+    type Address = RecordId;
+
+    fn is_null(&self) -> bool {
+        self.deleted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, Hasher};
+
+    // 所有地址的哈希都是 0，用来验证哈希冲突时依然按 Eq 区分条目
+    #[derive(Default)]
+    struct Collide;
+
+    impl Hasher for Collide {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, _: &[u8]) {}
+    }
+
+    fn host(name: &str, latency_ms: u32) -> Host {
+        Host {
+            name: name.to_string(),
+            latency_ms,
+        }
+    }
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let mut cache = Cache::new(3, Eviction::Lru);
+        for (i, ip) in [[10, 0, 0, 1], [10, 0, 0, 2], [10, 0, 0, 3]]
+            .into_iter()
+            .enumerate()
+        {
+            assert_eq!(cache.insert(ip, host("node", i as u32)), Insert::Stored);
+        }
+        assert_eq!(cache.get(&[10, 0, 0, 1]).unwrap().latency_ms, 0);
+        // 覆盖写入同样算一次访问
+        assert_eq!(
+            cache.insert([10, 0, 0, 2], host("node", 20)),
+            Insert::Replaced(host("node", 1))
+        );
+        assert_eq!(
+            cache.addresses(),
+            [[10, 0, 0, 3], [10, 0, 0, 1], [10, 0, 0, 2]]
+        );
+        assert_eq!(
+            cache.insert([10, 0, 0, 4], host("new", 4)),
+            Insert::Evicted([10, 0, 0, 3], host("node", 2))
+        );
+        assert!(!cache.contains(&[10, 0, 0, 3]));
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.stats().evictions, 1);
+    }
+
+    #[test]
+    fn fifo_ignores_access_order() {
+        let mut cache: Cache<Record> = Cache::new(2, Eviction::Fifo);
+        let record = |balance| Record {
+            balance,
+            deleted: false,
+        };
+        cache.insert(RecordId::from(1), record(100));
+        cache.insert(RecordId::from(2), record(200));
+        assert_eq!(cache.get(&RecordId::from(1)), Some(&record(100)));
+        cache.insert(RecordId::from(1), record(150));
+        assert_eq!(
+            cache.insert(RecordId::from(3), record(300)),
+            Insert::Evicted(RecordId::from(1), record(150))
+        );
+        let ids: Vec<u64> = cache.addresses().into_iter().map(u64::from).collect();
+        assert_eq!(ids, [2, 3]);
+        assert_eq!(cache.policy(), Eviction::Fifo);
+    }
+
+    #[test]
+    fn null_items_are_never_stored() {
+        let mut cache = Cache::new(4, Eviction::Lru);
+        let deleted = Record {
+            balance: 5,
+            deleted: true,
+        };
+        assert_eq!(
+            cache.insert(RecordId::from(9), deleted.clone()),
+            Insert::Rejected(deleted)
+        );
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(&RecordId::from(9)), None);
+
+        // 空条目也不会覆盖已有的条目
+        let mut pages = Cache::new(1, Eviction::Fifo);
+        pages.insert("/".to_string(), Page::new("home"));
+        assert_eq!(
+            pages.insert("/".to_string(), Page::default()),
+            Insert::Rejected(Page::default())
+        );
+        assert_eq!(pages.peek(&"/".to_string()), Some(&Page::new("home")));
+    }
+
+    #[test]
+    fn colliding_hashes_are_told_apart_by_eq() {
+        let mut cache: Cache<Page, BuildHasherDefault<Collide>> =
+            Cache::with_hasher(2, Eviction::Lru, BuildHasherDefault::default());
+        cache.insert("a".to_string(), Page::new("A"));
+        cache.insert("b".to_string(), Page::new("B"));
+        assert_eq!(cache.buckets.len(), 1);
+        assert_eq!(cache.get(&"a".to_string()), Some(&Page::new("A")));
+        assert_eq!(cache.peek(&"b".to_string()), Some(&Page::new("B")));
+        assert_eq!(cache.peek(&"c".to_string()), None);
+
+        cache.insert("c".to_string(), Page::new("C"));
+        assert!(!cache.contains(&"b".to_string()));
+        assert_eq!(cache.remove(&"a".to_string()), Some(Page::new("A")));
+        assert_eq!(cache.remove(&"a".to_string()), None);
+        assert_eq!(cache.addresses(), ["c"]);
+        cache.clear();
+        assert!(cache.is_empty() && cache.buckets.is_empty());
+    }
+
+    #[test]
+    fn tracks_hits_and_misses() {
+        let mut cache = Cache::new(8, Eviction::Lru);
+        cache.insert("k".to_string(), Page::new("v"));
+        cache.get(&"k".to_string());
+        cache.get(&"k".to_string());
+        cache.get(&"missing".to_string());
+        cache.peek(&"k".to_string());
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.evictions), (2, 1, 0));
+        assert_eq!(cache.capacity(), 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity must be positive")]
+    fn zero_capacity_is_rejected() {
+        Cache::<Page>::new(0, Eviction::Fifo);
+    }
+}
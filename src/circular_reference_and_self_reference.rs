@@ -1,5 +1,6 @@
 use std::{
     cell::RefCell,
+    collections::VecDeque,
     rc::{Rc, Weak},
 };
 
@@ -167,13 +168,7 @@ pub fn circular_reference_and_self_reference() {
     // 循环引用问题也就避免了
 
     // tree 数据结构
-    #[derive(Debug)]
-    struct Node {
-        value: i32,
-        parent: RefCell<Weak<Node>>,
-        children: RefCell<Vec<Rc<Node>>>,
-    }
-
+    // Node 定义在模块顶层
     let leaf = Rc::new(Node {
         value: 3,
         parent: RefCell::new(Weak::new()),
@@ -215,6 +210,27 @@ pub fn circular_reference_and_self_reference() {
         Rc::weak_count(&leaf),
     );
 
+    // 手动维护两个方向的链接很容易出错，Node 上的 add_child、detach、reparent 等方法把它们封装了起来：
+    let root = Node::new(1);
+    let left = Node::new(2);
+    root.add_child(Rc::clone(&left));
+    root.add_child(Node::new(3));
+    left.add_child(Node::new(4));
+    left.add_child(Node::new(5));
+    print!("{}", root.pretty());
+    let five = root.find(|n| n.value == 5).unwrap();
+    println!(
+        "depth of 5 = {}, path = {:?}",
+        five.depth(),
+        five.path_to_root()
+            .iter()
+            .map(|n| n.value)
+            .collect::<Vec<_>>()
+    );
+    let weak_root = Rc::downgrade(&root);
+    drop((root, left, five));
+    println!("root freed = {}", weak_root.upgrade().is_none());
+
     // unsafe 解决循环引用
     // 除了使用 Rust 标准库提供的这些类型，你还可以使用 unsafe 里的裸指针来解决这些棘手的问题，但是由于我们还没有讲解 unsafe，因此这里就不进行展开，只附上源码链接, 挺长的，需要耐心 o_o
     // 虽然 unsafe 不安全，但是在各种库的代码中依然很常见用它来实现自引用结构，主要优点如下:
@@ -225,3 +241,404 @@ pub fn circular_reference_and_self_reference() {
     // 本文深入讲解了何为循环引用以及如何使用 Weak 来解决，同时还结合 Rc、RefCell、Weak 等实现了两个有实战价值的例子，让大家对智能指针的使用更加融会贯通。
     // 至此，智能指针一章即将结束（严格来说还有一个 Mutex 放在多线程一章讲解），而 Rust 语言本身的学习之旅也即将结束，后面我们将深入多线程、项目工程、应用实践、性能分析等特色专题，来一睹 Rust 在这些领域的风采。
 }
+
+/// 树节点：父节点通过 `Rc` 拥有子节点，子节点通过 `Weak` 指回父节点，从而避免循环引用。
+///
+/// ```
+/// use std::{
+///     cell::RefCell,
+///     rc::{Rc, Weak},
+/// };
+/// use rust_thinking::circular_reference_and_self_reference::Node;
+///
+/// let leaf = Rc::new(Node {
+///     value: 3,
+///     parent: RefCell::new(Weak::new()),
+///     children: RefCell::new(vec![]),
+/// });
+/// let branch = Rc::new(Node {
+///     value: 5,
+///     parent: RefCell::new(Weak::new()),
+///     children: RefCell::new(vec![Rc::clone(&leaf)]),
+/// });
+/// *leaf.parent.borrow_mut() = Rc::downgrade(&branch);
+///
+/// assert_eq!(leaf.parent.borrow().upgrade().unwrap().value, 5);
+/// assert_eq!(Rc::strong_count(&branch), 1);
+/// assert_eq!(Rc::weak_count(&branch), 1);
+/// assert_eq!(Rc::strong_count(&leaf), 2);
+///
+/// drop(branch);
+/// assert!(leaf.parent.borrow().upgrade().is_none());
+/// ```
+#[derive(Debug)]
+pub struct Node {
+    pub value: i32,
+    pub parent: RefCell<Weak<Node>>,
+    pub children: RefCell<Vec<Rc<Node>>>,
+}
+
+/// [`Node::descendants`] 的遍历顺序。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Traversal {
+    /// 深度优先(先序)：先访问节点，再依次深入每个子树。
+    DepthFirst,
+    /// 广度优先：按层从上到下、每层从左到右。
+    BreadthFirst,
+}
+
+// 上面的例子手动设置 parent 和 children 两个方向的链接，这里把它们封装成树的操作：
+// 1. 修改结构的操作(add_child、detach、reparent)同时维护两个方向，保证"父节点的 children 中有我"和"我的 parent 指向父节点"总是同时成立
+// 2. 子节点永远不能持有父节点或祖先的强引用，add_child 会拒绝把祖先挂到后代下面，所以树中不会出现 Rc 循环，丢掉根节点就会释放整棵树
+// 3. 需要 Rc::downgrade 的方法把接收者写成 self: &Rc<Self>
+impl Node {
+    /// 创建一个没有父节点也没有子节点的节点。
+    ///
+    /// ```
+    /// use rust_thinking::circular_reference_and_self_reference::{Node, Traversal};
+    ///
+    /// let root = Node::new(1);
+    /// let a = Node::new(2);
+    /// root.add_child(a.clone());
+    /// root.add_child(Node::new(3));
+    /// a.add_child(Node::new(4));
+    ///
+    /// let values = |order| root.descendants(order).map(|n| n.value).collect::<Vec<_>>();
+    /// assert_eq!(values(Traversal::DepthFirst), [2, 4, 3]);
+    /// assert_eq!(values(Traversal::BreadthFirst), [2, 3, 4]);
+    /// assert_eq!(root.find(|n| n.value == 4).unwrap().depth(), 2);
+    /// print!("{}", root.pretty());
+    /// ```
+    pub fn new(value: i32) -> Rc<Node> {
+        Rc::new(Node {
+            value,
+            parent: RefCell::new(Weak::new()),
+            children: RefCell::new(Vec::new()),
+        })
+    }
+
+    pub fn parent(&self) -> Option<Rc<Node>> {
+        self.parent.borrow().upgrade()
+    }
+
+    /// 把 `child` 追加为最后一个子节点，并让它的 `parent` 指向自己。
+    ///
+    /// `child` 必须是一个没有父节点的根；它不能是自己或者自己的祖先，否则会形成 `Rc` 循环。
+    pub fn add_child(self: &Rc<Self>, child: Rc<Node>) {
+        assert!(
+            child.parent().is_none(),
+            "child already has a parent, use reparent to move it"
+        );
+        assert!(
+            !self.path_to_root().iter().any(|n| Rc::ptr_eq(n, &child)),
+            "adding an ancestor as a child would create an Rc cycle"
+        );
+        *child.parent.borrow_mut() = Rc::downgrade(self);
+        self.children.borrow_mut().push(child);
+    }
+
+    /// 从父节点上摘下自己(连同整个子树)，返回原来的父节点。
+    pub fn detach(self: &Rc<Self>) -> Option<Rc<Node>> {
+        let parent = self.parent()?;
+        parent
+            .children
+            .borrow_mut()
+            .retain(|child| !Rc::ptr_eq(child, self));
+        *self.parent.borrow_mut() = Weak::new();
+        Some(parent)
+    }
+
+    /// 把自己(连同整个子树)移到 `new_parent` 下面，`new_parent` 不能在自己的子树中。
+    pub fn reparent(self: &Rc<Self>, new_parent: &Rc<Node>) {
+        // 先检查再摘下，失败时树保持不变
+        assert!(
+            !new_parent
+                .path_to_root()
+                .iter()
+                .any(|n| Rc::ptr_eq(n, self)),
+            "adding an ancestor as a child would create an Rc cycle"
+        );
+        self.detach();
+        new_parent.add_child(Rc::clone(self));
+    }
+
+    /// 从父节点一直到根节点，不包括自己。
+    pub fn ancestors(&self) -> impl Iterator<Item = Rc<Node>> {
+        std::iter::successors(self.parent(), |node| node.parent())
+    }
+
+    /// 自己子树中的所有节点，不包括自己。
+    pub fn descendants(&self, order: Traversal) -> Descendants {
+        Descendants {
+            pending: self.children.borrow().iter().cloned().collect(),
+            order,
+        }
+    }
+
+    /// 根节点的深度为 0。
+    pub fn depth(&self) -> usize {
+        self.ancestors().count()
+    }
+
+    pub fn root(self: &Rc<Self>) -> Rc<Node> {
+        self.ancestors().last().unwrap_or_else(|| Rc::clone(self))
+    }
+
+    /// 从自己到根节点的路径，包括两端。
+    pub fn path_to_root(self: &Rc<Self>) -> Vec<Rc<Node>> {
+        std::iter::once(Rc::clone(self))
+            .chain(self.ancestors())
+            .collect()
+    }
+
+    /// 按深度优先的顺序在自己的子树(包括自己)中查找第一个满足 `pred` 的节点。
+    pub fn find(self: &Rc<Self>, mut pred: impl FnMut(&Node) -> bool) -> Option<Rc<Node>> {
+        if pred(self) {
+            return Some(Rc::clone(self));
+        }
+        self.descendants(Traversal::DepthFirst).find(|n| pred(n))
+    }
+
+    /// 用制表符画出以自己为根的子树，每个节点一行。
+    pub fn pretty(&self) -> String {
+        // 栈中保存待画的节点、它的前缀以及它是否是最后一个子节点；子节点逆序入栈，出栈时就是先序
+        fn push_children(node: &Node, prefix: &str, stack: &mut Vec<(Rc<Node>, String, bool)>) {
+            let children = node.children.borrow();
+            for (i, child) in children.iter().enumerate().rev() {
+                stack.push((Rc::clone(child), prefix.to_string(), i + 1 == children.len()));
+            }
+        }
+        let mut out = format!("{}\n", self.value);
+        let mut stack = Vec::new();
+        push_children(self, "", &mut stack);
+        while let Some((node, prefix, last)) = stack.pop() {
+            let (branch, indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            out.push_str(&format!("{}{}{}\n", prefix, branch, node.value));
+            push_children(&node, &format!("{}{}", prefix, indent), &mut stack);
+        }
+        out
+    }
+}
+
+// 默认的析构会递归地析构 children，很深的树(例如一条长链)会栈溢出。
+// 这里先把 children 移到一个工作列表中，再逐个取出：只有这里持有的子节点才拆开、把它的 children 继续放进列表，
+// 还被别处持有的子节点只减少引用计数。每个节点析构时 children 都已经是空的，不会再递归。
+impl Drop for Node {
+    fn drop(&mut self) {
+        let mut pending = std::mem::take(self.children.get_mut());
+        while let Some(child) = pending.pop() {
+            if let Ok(mut child) = Rc::try_unwrap(child) {
+                pending.append(child.children.get_mut());
+            }
+        }
+    }
+}
+
+/// [`Node::descendants`] 返回的迭代器。
+pub struct Descendants {
+    pending: VecDeque<Rc<Node>>,
+    order: Traversal,
+}
+
+impl Iterator for Descendants {
+    type Item = Rc<Node>;
+
+    fn next(&mut self) -> Option<Rc<Node>> {
+        let node = self.pending.pop_front()?;
+        let children = node.children.borrow();
+        match self.order {
+            // 子节点插到队首，下一个访问的就是第一个子节点
+            Traversal::DepthFirst => {
+                for child in children.iter().rev() {
+                    self.pending.push_front(Rc::clone(child));
+                }
+            }
+            Traversal::BreadthFirst => self.pending.extend(children.iter().cloned()),
+        }
+        drop(children);
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1
+    // ├── 2
+    // │   ├── 4
+    // │   └── 5
+    // │       └── 7
+    // └── 3
+    //     └── 6
+    fn sample() -> Rc<Node> {
+        let nodes: Vec<Rc<Node>> = (0..8).map(Node::new).collect();
+        for (parent, child) in [(1, 2), (1, 3), (2, 4), (2, 5), (3, 6), (5, 7)] {
+            nodes[parent].add_child(Rc::clone(&nodes[child]));
+        }
+        Rc::clone(&nodes[1])
+    }
+
+    fn values(nodes: impl IntoIterator<Item = Rc<Node>>) -> Vec<i32> {
+        nodes.into_iter().map(|n| n.value).collect()
+    }
+
+    // 记录子树中每个节点的弱引用，用来检查节点是否都被释放
+    fn weak_handles(root: &Rc<Node>) -> Vec<Weak<Node>> {
+        std::iter::once(Rc::clone(root))
+            .chain(root.descendants(Traversal::BreadthFirst))
+            .map(|n| Rc::downgrade(&n))
+            .collect()
+    }
+
+    #[test]
+    fn traversals_and_queries() {
+        let root = sample();
+        assert_eq!(
+            values(root.descendants(Traversal::DepthFirst)),
+            [2, 4, 5, 7, 3, 6]
+        );
+        assert_eq!(
+            values(root.descendants(Traversal::BreadthFirst)),
+            [2, 3, 4, 5, 6, 7]
+        );
+
+        let seven = root.find(|n| n.value == 7).unwrap();
+        assert_eq!(values(seven.ancestors()), [5, 2, 1]);
+        assert_eq!(values(seven.path_to_root()), [7, 5, 2, 1]);
+        assert_eq!(seven.depth(), 3);
+        assert_eq!(root.depth(), 0);
+        assert!(Rc::ptr_eq(&seven.root(), &root));
+        assert!(root.find(|n| n.value == 42).is_none());
+        assert_eq!(root.find(|n| n.value % 2 == 1).unwrap().value, 1);
+        assert_eq!(root.find(|n| n.value > 4).unwrap().value, 5);
+    }
+
+    #[test]
+    fn pretty_draws_box_characters() {
+        let expected = "\
+1
+├── 2
+│   ├── 4
+│   └── 5
+│       └── 7
+└── 3
+    └── 6
+";
+        assert_eq!(sample().pretty(), expected);
+        assert_eq!(Node::new(9).pretty(), "9\n");
+    }
+
+    #[test]
+    fn detach_and_reparent_keep_both_links_in_sync() {
+        let root = sample();
+        let two = root.find(|n| n.value == 2).unwrap();
+        let three = root.find(|n| n.value == 3).unwrap();
+
+        assert!(Rc::ptr_eq(&two.detach().unwrap(), &root));
+        assert!(two.detach().is_none());
+        assert!(two.parent().is_none());
+        assert_eq!(values(root.descendants(Traversal::DepthFirst)), [3, 6]);
+
+        two.reparent(&three);
+        assert_eq!(
+            values(root.descendants(Traversal::DepthFirst)),
+            [3, 6, 2, 4, 5, 7]
+        );
+        assert_eq!(root.find(|n| n.value == 7).unwrap().depth(), 4);
+
+        // 在同一个父节点下 reparent 相当于移到最后
+        let six = root.find(|n| n.value == 6).unwrap();
+        six.reparent(&three);
+        assert_eq!(values(three.children.borrow().iter().cloned()), [2, 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "would create an Rc cycle")]
+    fn adding_an_ancestor_panics() {
+        let root = sample();
+        let five = root.find(|n| n.value == 5).unwrap();
+        let two = five.parent().unwrap();
+        two.detach();
+        // 2 已经是根，但它是 5 的祖先
+        five.add_child(two);
+    }
+
+    #[test]
+    fn failed_reparent_leaves_tree_unchanged() {
+        let root = sample();
+        let two = root.find(|n| n.value == 2).unwrap();
+        let seven = root.find(|n| n.value == 7).unwrap();
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| two.reparent(&seven)));
+        assert!(result.is_err());
+        assert_eq!(
+            values(root.descendants(Traversal::DepthFirst)),
+            [2, 4, 5, 7, 3, 6]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "child already has a parent")]
+    fn add_child_requires_a_root() {
+        let root = sample();
+        let four = root.find(|n| n.value == 4).unwrap();
+        Node::new(0).add_child(four);
+    }
+
+    #[test]
+    fn dropping_the_root_frees_every_node() {
+        let root = sample();
+        let handles = weak_handles(&root);
+        assert_eq!(handles.len(), 7);
+        // 子节点只持有父节点的弱引用，每个节点的强引用只来自父节点(根节点来自 root 变量)
+        assert!(handles.iter().all(|w| w.strong_count() == 1));
+        drop(root);
+        assert!(handles.iter().all(|w| w.strong_count() == 0));
+    }
+
+    #[test]
+    fn deep_chain_drops_without_overflowing_the_stack() {
+        // 自底向上建链，每次 add_child 的父节点都是根，避免 path_to_root 的开销
+        let leaf = Node::new(0);
+        let weak_leaf = Rc::downgrade(&leaf);
+        let mut top = leaf;
+        for value in 1..100_000 {
+            let parent = Node::new(value);
+            parent.add_child(top);
+            top = parent;
+        }
+        assert_eq!(weak_leaf.upgrade().unwrap().depth(), 99_999);
+        assert_eq!(top.descendants(Traversal::DepthFirst).count(), 99_999);
+        drop(top);
+        assert_eq!(weak_leaf.strong_count(), 0);
+    }
+
+    #[test]
+    fn detached_subtrees_are_freed_independently() {
+        let root = sample();
+        let all = weak_handles(&root);
+        let two = root.find(|n| n.value == 2).unwrap();
+        let subtree = weak_handles(&two);
+        two.detach();
+        drop(two);
+        // 子树 2 没有人持有，立即释放；其余节点仍然存活
+        assert!(subtree.iter().all(|w| w.strong_count() == 0));
+        assert_eq!(all.iter().filter(|w| w.strong_count() > 0).count(), 3);
+
+        // reparent 之后只有新的父节点持有它
+        let three = root.find(|n| n.value == 3).unwrap();
+        let other = Node::new(10);
+        three.reparent(&other);
+        assert_eq!(Rc::strong_count(&three), 2);
+        drop(three);
+        drop(root);
+        assert_eq!(all.iter().filter(|w| w.strong_count() > 0).count(), 2);
+        drop(other);
+        assert!(all.iter().all(|w| w.strong_count() == 0));
+    }
+}
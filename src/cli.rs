@@ -0,0 +1,290 @@
+use std::{io, path::Path, process};
+
+use rust_thinking::{
+    layout,
+    registry::{self, Chapter},
+    runner, snapshot,
+};
+
+const USAGE: &str = "\
+usage: rust_thinking <command>
+
+commands:
+    list                      列出所有章节及其标签
+    run <chapter>...          按给定顺序运行指定章节
+    run --tag <tag>           运行带有该标签的所有章节
+    run --all                 按学习顺序运行全部章节
+    verify [<selection>]      比对章节输出与 snapshots/ 下的快照，不指定章节时比对全部章节
+    verify ... --update       用当前输出覆盖快照
+    layout                    打印智能指针组合和章节类型的内存布局及堆分配";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    List,
+    Run(Selection),
+    Verify { selection: Selection, update: bool },
+    Layout,
+    // 供快照捕获使用：直接运行单个章节，不打印标题和汇总
+    Exec(String),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Selection {
+    Names(Vec<String>),
+    Tag(String),
+    All,
+}
+
+impl Command {
+    pub fn parse<I>(args: I) -> Result<Command, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+        match args.next().as_deref() {
+            None | Some("help") | Some("-h") | Some("--help") => Ok(Command::Help),
+            Some("list") => match args.next() {
+                None => Ok(Command::List),
+                Some(extra) => Err(format!("unexpected argument `{}` for `list`", extra)),
+            },
+            Some("run") => Selection::parse(args).map(Command::Run),
+            Some("verify") => {
+                let (update, rest): (Vec<String>, Vec<String>) =
+                    args.partition(|a| a == "--update");
+                let selection = if rest.is_empty() {
+                    Selection::All
+                } else {
+                    Selection::parse(rest.into_iter())?
+                };
+                Ok(Command::Verify {
+                    selection,
+                    update: !update.is_empty(),
+                })
+            }
+            Some("layout") => match args.next() {
+                None => Ok(Command::Layout),
+                Some(extra) => Err(format!("unexpected argument `{}` for `layout`", extra)),
+            },
+            Some("exec") => match (args.next(), args.next()) {
+                (Some(name), None) => Ok(Command::Exec(name)),
+                _ => Err("`exec` takes exactly one chapter name".to_string()),
+            },
+            Some(other) => Err(format!("unknown command `{}`", other)),
+        }
+    }
+}
+
+impl Selection {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Selection, String> {
+        let args: Vec<String> = args.collect();
+        match args.first().map(String::as_str) {
+            None => Err("`run` needs chapter names, `--tag <tag>` or `--all`".to_string()),
+            Some("--all") if args.len() == 1 => Ok(Selection::All),
+            Some("--tag") if args.len() == 2 => Ok(Selection::Tag(args[1].clone())),
+            Some("--all") | Some("--tag") => Err(format!("invalid arguments: {}", args.join(" "))),
+            Some(_) => match args.iter().find(|a| a.starts_with('-')) {
+                Some(flag) => Err(format!("unknown flag `{}`", flag)),
+                None => Ok(Selection::Names(args)),
+            },
+        }
+    }
+
+    // 把选择条件解析成注册表中的章节，名字拼错或者标签不存在都算错误
+    pub fn resolve(&self) -> Result<Vec<&'static Chapter>, String> {
+        match self {
+            Selection::All => Ok(registry::CHAPTERS.iter().collect()),
+            Selection::Tag(tag) => {
+                let chapters: Vec<_> = registry::with_tag(tag).collect();
+                if chapters.is_empty() {
+                    Err(format!(
+                        "no chapter is tagged `{}`, known tags: {}",
+                        tag,
+                        registry::tags().join(", ")
+                    ))
+                } else {
+                    Ok(chapters)
+                }
+            }
+            Selection::Names(names) => names
+                .iter()
+                .map(|name| {
+                    registry::find(name).ok_or_else(|| {
+                        format!("unknown chapter `{}`, see `rust_thinking list`", name)
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn list() {
+    let width = registry::CHAPTERS
+        .iter()
+        .map(|c| c.name.len())
+        .max()
+        .unwrap_or(0);
+    for chapter in registry::CHAPTERS {
+        println!(
+            "{:<width$}  {}  [{}]",
+            chapter.name,
+            chapter.title,
+            chapter.tags.join(", "),
+            width = width
+        );
+    }
+}
+
+// 运行选中的章节并打印汇总表，只要有章节 panic 就以非零状态码退出
+pub fn run(chapters: &[&'static Chapter]) {
+    let results = runner::run_chapters(chapters);
+    print!("\n{}", runner::summary(&results));
+    if results.iter().any(|r| !r.passed()) {
+        process::exit(1);
+    }
+}
+
+// 比对快照，有章节输出不一致或者运行失败时以非零状态码退出
+pub fn verify(chapters: &[&'static Chapter], update: bool) {
+    let result = snapshot::Subprocess::current().and_then(|capture| {
+        snapshot::verify(
+            chapters,
+            Path::new(snapshot::SNAPSHOT_DIR),
+            update,
+            &capture,
+            &mut io::stdout(),
+        )
+    });
+    match result {
+        Ok(statuses) => {
+            let failed = statuses
+                .iter()
+                .filter(|s| matches!(s, snapshot::Status::Mismatched | snapshot::Status::Failed))
+                .count();
+            println!("\n{} chapters checked, {} failed", statuses.len(), failed);
+            if failed > 0 {
+                process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(2);
+        }
+    }
+}
+
+pub fn main() {
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match command {
+        Command::Help => println!("{}", USAGE),
+        Command::List => list(),
+        Command::Run(selection) => run(&resolve_or_exit(&selection)),
+        Command::Verify { selection, update } => verify(&resolve_or_exit(&selection), update),
+        Command::Layout => print!("{}", layout::render(&layout::table())),
+        Command::Exec(name) => match registry::find(&name) {
+            Some(chapter) => (chapter.entry)(),
+            None => {
+                eprintln!("error: unknown chapter `{}`", name);
+                process::exit(2);
+            }
+        },
+    }
+}
+
+fn resolve_or_exit(selection: &Selection) -> Vec<&'static Chapter> {
+    selection.resolve().unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        process::exit(2);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        Command::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(parse(&[]), Ok(Command::Help));
+        assert_eq!(parse(&["list"]), Ok(Command::List));
+        assert_eq!(parse(&["run", "--all"]), Ok(Command::Run(Selection::All)));
+        assert_eq!(
+            parse(&["run", "--tag", "smart-pointers"]),
+            Ok(Command::Run(Selection::Tag("smart-pointers".to_string())))
+        );
+        assert_eq!(
+            parse(&["run", "closure", "refcell"]),
+            Ok(Command::Run(Selection::Names(vec![
+                "closure".to_string(),
+                "refcell".to_string()
+            ])))
+        );
+        assert!(parse(&["run"]).is_err());
+        assert!(parse(&["run", "--tag"]).is_err());
+        assert!(parse(&["run", "closure", "--all"]).is_err());
+        assert!(parse(&["list", "closure"]).is_err());
+        assert!(parse(&["fly"]).is_err());
+        assert_eq!(
+            parse(&["verify"]),
+            Ok(Command::Verify {
+                selection: Selection::All,
+                update: false
+            })
+        );
+        assert_eq!(
+            parse(&["verify", "learn_drop", "--update"]),
+            Ok(Command::Verify {
+                selection: Selection::Names(vec!["learn_drop".to_string()]),
+                update: true
+            })
+        );
+        assert_eq!(parse(&["layout"]), Ok(Command::Layout));
+        assert!(parse(&["layout", "Rc"]).is_err());
+        assert_eq!(
+            parse(&["exec", "closure"]),
+            Ok(Command::Exec("closure".to_string()))
+        );
+    }
+
+    #[test]
+    fn resolve_selection() {
+        let names = Selection::Names(vec!["refcell".to_string(), "closure".to_string()]);
+        let chapters = names.resolve().unwrap();
+        assert_eq!(chapters[0].name, "refcell");
+        assert_eq!(chapters[1].name, "closure");
+
+        let tagged = Selection::Tag("smart-pointers".to_string())
+            .resolve()
+            .unwrap();
+        assert!(tagged.iter().all(|c| c.has_tag("smart-pointers")));
+        assert!(tagged.iter().any(|c| c.name == "refcell"));
+
+        assert_eq!(
+            Selection::All.resolve().unwrap().len(),
+            registry::CHAPTERS.len()
+        );
+        assert!(Selection::Names(vec!["nope".to_string()])
+            .resolve()
+            .is_err());
+        assert!(Selection::Tag("nope".to_string()).resolve().is_err());
+    }
+
+    #[test]
+    fn chapter_names_are_unique() {
+        for (i, chapter) in registry::CHAPTERS.iter().enumerate() {
+            assert!(registry::CHAPTERS[i + 1..]
+                .iter()
+                .all(|other| other.name != chapter.name));
+        }
+    }
+}
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+use std::hash::Hash;
 use std::thread;
 use std::time::Duration;
 
+use crate::memo::Memo;
+
 pub fn closure() {
     // 闭包是一种匿名函数，它可以赋值给变量也可以作为参数传递给其它函数，不同于函数的是，它允许捕获调用者作用域中的值，例如：
     let x = 1;
@@ -140,39 +144,17 @@ pub fn closure() {
     // 特征 Fn(u32) -> u32 从表面来看，就对闭包形式进行了显而易见的限制：该闭包拥有一个u32类型的参数，同时返回一个u32类型的值。
     // 需要注意的是，其实 Fn 特征不仅仅适用于闭包，还适用于函数，因此上面的 query 字段除了使用闭包作为值外，还能使用一个具名的函数来作为它的值
 
-    struct Cacher1<T, E>
-    where
-        T: Fn(E) -> E,
-        E: Copy,
-    {
-        query: T,
-        value: Option<E>,
-    }
-
-    impl<T, E> Cacher1<T, E>
-    where
-        T: Fn(E) -> E,
-        E: Copy,
-    {
-        fn new(query: T) -> Cacher1<T, E> {
-            Cacher1 { query, value: None }
-        }
-
-        fn value(&mut self, arg: E) -> E {
-            match self.value {
-                Some(v) => v,
-                None => {
-                    let v = (self.query)(arg);
-                    self.value = Some(v);
-                    v
-                }
-            }
-        }
-    }
     let mut c = Cacher1::new(|a| a);
     let v1 = c.value(1);
     let v2 = c.value(2);
-    assert_eq!(v2, 1);
+    assert_eq!(v2, 2);
+    // Cacher1 以参数为 key 缓存结果，不同的参数各自缓存。memo::Memo 在此基础上增加了容量限制(LRU)和命中统计，
+    // 用它包装前面健身例子中耗时的 muuuuu，同样的强度只会 muuuu 一次：
+    let mut action = Memo::with_capacity(|intensity: &u32| muuuuu(*intensity), 2);
+    for intensity in [10, 10, 20, 10, 30, 20] {
+        println!("做 {} 个俯卧撑!", action.get(intensity));
+    }
+    println!("{:?}", action.stats());
 
     // 捕获作用域中的值
     // 在之前代码中，我们一直在用闭包的匿名函数特性（赋值给变量），然而闭包还拥有一项函数所不具备的特性：捕获作用域中的值。
@@ -334,17 +316,65 @@ pub fn closure() {
 
     // 闭包作为函数返回值
     // 看到这里，相信大家对于如何使用闭包作为函数参数，已经很熟悉了，但是如果要使用闭包作为函数返回值，该如何做 ？
-    fn factory(x: i32) -> Box<dyn Fn(i32) -> i32> {
-        let num = 5;
+    let answer = factory(1);
+    let c = answer(1);
+    println!("{}", c);
+}
 
-        if x > 1 {
-            Box::new(move |x| x + num)
-        } else {
-            Box::new(move |x| x - num)
+/// 缓存闭包执行结果的结构体：以参数为 key 缓存结果，同一个参数只会执行一次闭包。
+///
+/// 最初的版本只用一个 `Option<E>` 保存结果，第二次用不同的参数调用时返回的依然是第一次的结果，
+/// 现在每个参数都有自己的缓存；需要容量限制、命中统计或者多线程共享时可以使用 [`crate::memo::Memo`]。
+///
+/// ```
+/// use rust_thinking::closure::Cacher1;
+///
+/// let mut c = Cacher1::new(|a| a * 10);
+/// assert_eq!(c.value(1), 10);
+/// assert_eq!(c.value(2), 20);
+/// assert_eq!(c.value(1), 10);
+/// ```
+pub struct Cacher1<T, E>
+where
+    T: Fn(E) -> E,
+    E: Copy + Eq + Hash,
+{
+    query: T,
+    values: HashMap<E, E>,
+}
+
+impl<T, E> Cacher1<T, E>
+where
+    T: Fn(E) -> E,
+    E: Copy + Eq + Hash,
+{
+    pub fn new(query: T) -> Cacher1<T, E> {
+        Cacher1 {
+            query,
+            values: HashMap::new(),
         }
     }
 
-    let answer = factory(1);
-    let c = answer(1);
-    println!("{}", c);
+    pub fn value(&mut self, arg: E) -> E {
+        let query = &self.query;
+        *self.values.entry(arg).or_insert_with(|| query(arg))
+    }
+}
+
+/// 根据参数在运行时选择返回哪一个闭包，闭包被装箱成 `Box<dyn Fn(i32) -> i32>` 特征对象返回。
+///
+/// ```
+/// use rust_thinking::closure::factory;
+///
+/// assert_eq!(factory(2)(1), 6);
+/// assert_eq!(factory(1)(1), -4);
+/// ```
+pub fn factory(x: i32) -> Box<dyn Fn(i32) -> i32> {
+    let num = 5;
+
+    if x > 1 {
+        Box::new(move |x| x + num)
+    } else {
+        Box::new(move |x| x - num)
+    }
 }
@@ -0,0 +1,365 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
+
+// 容器特征
+// deep_trait 章节对比了容器特征的泛型参数和关联类型两种写法，用的就是这里的 GenericContainer 和 Container。
+// 这里把它们做成真正可用的特征：容器中保存的是 (A, B) 对，Vec<(A, B)>、HashMap、BTreeMap 和自定义的 Pairs 都实现了它。
+// 1. Container：关联类型版本，每个容器类型只能有一组 A、B，使用时不需要写出 A、B
+// 2. GenericContainer<A, B>：泛型参数版本，由 Container 自动实现，使用时每个函数都要带上 A、B 两个泛型参数
+// difference 和 difference_generic 是同一个集合差运算的两种写法，可以对比它们的函数签名。
+
+/// 保存 `(A, B)` 对的容器，`A`、`B` 是关联类型。
+pub trait Container {
+    type A;
+    type B;
+
+    fn contains(&self, a: &Self::A, b: &Self::B) -> bool;
+
+    // Vec 和 Pairs 按插入顺序，BTreeMap 按键的大小，HashMap 的顺序不确定
+    fn first(&self) -> Option<(&Self::A, &Self::B)>;
+    fn last(&self) -> Option<(&Self::A, &Self::B)>;
+
+    fn pairs(&self) -> impl Iterator<Item = (&Self::A, &Self::B)>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// [`Container`] 的泛型参数版本，所有 `Container` 都自动实现了它。
+///
+/// 两个特征的方法同名，同时引入时需要用 `Container::first(&c)` 这样的完全限定语法调用。
+pub trait GenericContainer<A, B> {
+    fn contains(&self, a: &A, b: &B) -> bool;
+    fn first(&self) -> Option<(&A, &B)>;
+    fn last(&self) -> Option<(&A, &B)>;
+    fn pairs<'a>(&'a self) -> impl Iterator<Item = (&'a A, &'a B)>
+    where
+        A: 'a,
+        B: 'a;
+}
+
+impl<C: Container> GenericContainer<C::A, C::B> for C {
+    fn contains(&self, a: &C::A, b: &C::B) -> bool {
+        Container::contains(self, a, b)
+    }
+
+    fn first(&self) -> Option<(&C::A, &C::B)> {
+        Container::first(self)
+    }
+
+    fn last(&self) -> Option<(&C::A, &C::B)> {
+        Container::last(self)
+    }
+
+    fn pairs<'a>(&'a self) -> impl Iterator<Item = (&'a C::A, &'a C::B)>
+    where
+        C::A: 'a,
+        C::B: 'a,
+    {
+        Container::pairs(self)
+    }
+}
+
+/// 集合差：`left` 中不在 `right` 里的所有 `(A, B)` 对，按 `left` 中第一次出现的顺序返回。
+///
+/// 两个容器可以是不同的类型，只要关联类型相同。`left` 中重复的 `(A, B)` 对只返回一次。
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use rust_thinking::container::difference;
+///
+/// let scores = vec![("alice", 90), ("bob", 72), ("carol", 85)];
+/// let checked = BTreeMap::from([("alice", 90), ("bob", 70)]);
+/// assert_eq!(difference(&scores, &checked), [(&"bob", &72), (&"carol", &85)]);
+/// ```
+pub fn difference<'a, L, R>(left: &'a L, right: &R) -> Vec<(&'a L::A, &'a L::B)>
+where
+    L: Container,
+    L::A: PartialEq,
+    L::B: PartialEq,
+    R: Container<A = L::A, B = L::B>,
+{
+    let mut out = Vec::new();
+    for (a, b) in left.pairs() {
+        if !right.contains(a, b) && !out.iter().any(|&(x, y)| x == a && y == b) {
+            out.push((a, b));
+        }
+    }
+    out
+}
+
+/// 和 [`difference`] 相同的运算，用泛型参数版本的特征来写，`A`、`B` 必须出现在函数签名中。
+pub fn difference_generic<'a, A, B, L, R>(left: &'a L, right: &R) -> Vec<(&'a A, &'a B)>
+where
+    A: PartialEq + 'a,
+    B: PartialEq + 'a,
+    L: GenericContainer<A, B>,
+    R: GenericContainer<A, B>,
+{
+    let mut out = Vec::new();
+    for (a, b) in left.pairs() {
+        if !right.contains(a, b) && !out.iter().any(|&(x, y)| x == a && y == b) {
+            out.push((a, b));
+        }
+    }
+    out
+}
+
+impl<A: PartialEq, B: PartialEq> Container for Vec<(A, B)> {
+    type A = A;
+    type B = B;
+
+    fn contains(&self, a: &A, b: &B) -> bool {
+        self.iter().any(|(x, y)| x == a && y == b)
+    }
+
+    fn first(&self) -> Option<(&A, &B)> {
+        self.as_slice().first().map(|(a, b)| (a, b))
+    }
+
+    fn last(&self) -> Option<(&A, &B)> {
+        self.as_slice().last().map(|(a, b)| (a, b))
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (&A, &B)> {
+        self.iter().map(|(a, b)| (a, b))
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+}
+
+impl<A: Eq + Hash, B: PartialEq> Container for HashMap<A, B> {
+    type A = A;
+    type B = B;
+
+    fn contains(&self, a: &A, b: &B) -> bool {
+        self.get(a) == Some(b)
+    }
+
+    fn first(&self) -> Option<(&A, &B)> {
+        self.iter().next()
+    }
+
+    fn last(&self) -> Option<(&A, &B)> {
+        self.iter().last()
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (&A, &B)> {
+        self.iter()
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+impl<A: Ord, B: PartialEq> Container for BTreeMap<A, B> {
+    type A = A;
+    type B = B;
+
+    fn contains(&self, a: &A, b: &B) -> bool {
+        self.get(a) == Some(b)
+    }
+
+    fn first(&self) -> Option<(&A, &B)> {
+        self.first_key_value()
+    }
+
+    fn last(&self) -> Option<(&A, &B)> {
+        self.last_key_value()
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (&A, &B)> {
+        self.iter()
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+}
+
+/// 把 `(A, B)` 对拆成两列保存的容器，deep_trait 章节用它演示自定义类型实现 [`Container`]。
+///
+/// ```
+/// use rust_thinking::container::{Container, Pairs};
+///
+/// let mut pairs = Pairs::new();
+/// pairs.push(1, 'a');
+/// pairs.push(2, 'b');
+/// assert!(pairs.contains(&2, &'b'));
+/// assert!(!pairs.contains(&2, &'a'));
+/// assert_eq!(pairs.last(), Some((&2, &'b')));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pairs<A, B> {
+    firsts: Vec<A>,
+    seconds: Vec<B>,
+}
+
+impl<A, B> Default for Pairs<A, B> {
+    fn default() -> Pairs<A, B> {
+        Pairs::new()
+    }
+}
+
+impl<A, B> Pairs<A, B> {
+    pub fn new() -> Pairs<A, B> {
+        Pairs {
+            firsts: Vec::new(),
+            seconds: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, a: A, b: B) {
+        self.firsts.push(a);
+        self.seconds.push(b);
+    }
+}
+
+impl<A, B> FromIterator<(A, B)> for Pairs<A, B> {
+    fn from_iter<I: IntoIterator<Item = (A, B)>>(iter: I) -> Pairs<A, B> {
+        let (firsts, seconds) = iter.into_iter().unzip();
+        Pairs { firsts, seconds }
+    }
+}
+
+impl<A: PartialEq, B: PartialEq> Container for Pairs<A, B> {
+    type A = A;
+    type B = B;
+
+    fn contains(&self, a: &A, b: &B) -> bool {
+        Container::pairs(self).any(|(x, y)| x == a && y == b)
+    }
+
+    fn first(&self) -> Option<(&A, &B)> {
+        self.firsts.first().zip(self.seconds.first())
+    }
+
+    fn last(&self) -> Option<(&A, &B)> {
+        self.firsts.last().zip(self.seconds.last())
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (&A, &B)> {
+        self.firsts.iter().zip(&self.seconds)
+    }
+
+    fn len(&self) -> usize {
+        self.firsts.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::Rng;
+    use std::collections::HashSet;
+
+    fn random_pairs(rng: &mut Rng, len: usize) -> Vec<(u8, u8)> {
+        (0..len)
+            .map(|_| (rng.below(8) as u8, rng.below(3) as u8))
+            .collect()
+    }
+
+    fn sorted<'a>(pairs: Vec<(&'a u8, &'a u8)>) -> Vec<(u8, u8)> {
+        let mut pairs: Vec<(u8, u8)> = pairs.into_iter().map(|(a, b)| (*a, *b)).collect();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn first_last_and_contains() {
+        let v = vec![("b", 2), ("a", 1)];
+        assert_eq!(Container::first(&v), Some((&"b", &2)));
+        assert_eq!(Container::last(&v), Some((&"a", &1)));
+        assert!(Container::contains(&v, &"a", &1));
+        assert!(!Container::contains(&v, &"a", &2));
+
+        let tree: BTreeMap<_, _> = v.iter().copied().collect();
+        assert_eq!(Container::first(&tree), Some((&"a", &1)));
+        assert_eq!(Container::last(&tree), Some((&"b", &2)));
+
+        let map: HashMap<_, _> = v.iter().copied().collect();
+        assert!(Container::contains(&map, &"b", &2));
+        assert!(!Container::contains(&map, &"c", &2));
+        // HashMap 的 first/last 顺序不确定，但一定是其中的元素
+        let (a, b) = Container::first(&map).unwrap();
+        assert!(Container::contains(&v, a, b));
+
+        let pairs: Pairs<&str, i32> = v.into_iter().collect();
+        assert_eq!(Container::first(&pairs), Some((&"b", &2)));
+        assert_eq!(Container::len(&pairs), 2);
+
+        let empty: Pairs<u8, u8> = Pairs::default();
+        assert!(Container::is_empty(&empty));
+        assert_eq!(Container::first(&empty), None);
+        assert_eq!(GenericContainer::last(&empty), None);
+    }
+
+    #[test]
+    fn difference_works_across_container_types() {
+        let left = vec![(1, 'a'), (2, 'b'), (3, 'c'), (2, 'b'), (1, 'a')];
+        let right: HashMap<i32, char> = HashMap::from([(2, 'b'), (3, 'x')]);
+        // 重复的 (1, 'a') 只返回一次
+        assert_eq!(difference(&left, &right), [(&1, &'a'), (&3, &'c')]);
+        assert_eq!(difference_generic(&left, &right), [(&1, &'a'), (&3, &'c')]);
+
+        let pairs: Pairs<i32, char> = [(3, 'c'), (9, 'z')].into_iter().collect();
+        assert_eq!(difference(&pairs, &left), [(&9, &'z')]);
+        assert!(difference(&left, &left).is_empty());
+    }
+
+    #[test]
+    fn generic_and_associated_versions_agree() {
+        let mut rng = Rng::new(18);
+        for _ in 0..200 {
+            let (left_len, right_len) = (rng.below(12) as usize, rng.below(12) as usize);
+            let left = random_pairs(&mut rng, left_len);
+            let right = random_pairs(&mut rng, right_len);
+
+            // 用 HashSet 计算的参考结果
+            let right_set: HashSet<(u8, u8)> = right.iter().copied().collect();
+            let mut expected: Vec<(u8, u8)> = left
+                .iter()
+                .copied()
+                .filter(|p| !right_set.contains(p))
+                .collect();
+            expected.sort();
+            expected.dedup();
+
+            let as_assoc = difference(&left, &right);
+            let as_generic = difference_generic(&left, &right);
+            assert_eq!(as_assoc, as_generic);
+            assert_eq!(sorted(as_assoc), expected);
+
+            // 右侧换成 Pairs，结果也一样
+            let right_pairs: Pairs<u8, u8> = right.iter().copied().collect();
+            assert_eq!(
+                difference(&left, &right_pairs),
+                difference_generic(&left, &right_pairs)
+            );
+            assert_eq!(sorted(difference(&left, &right_pairs)), expected);
+
+            // 映射中每个键只保留最后一次写入的值，差集按去重后的键值对计算
+            let left_tree: BTreeMap<u8, u8> = left.iter().copied().collect();
+            let right_map: HashMap<u8, u8> = right.iter().copied().collect();
+            let mut map_expected: Vec<(u8, u8)> = left_tree
+                .iter()
+                .filter(|(a, b)| right_map.get(a) != Some(b))
+                .map(|(a, b)| (*a, *b))
+                .collect();
+            map_expected.sort();
+            assert_eq!(
+                difference(&left_tree, &right_map),
+                difference_generic(&left_tree, &right_map)
+            );
+            assert_eq!(sorted(difference(&left_tree, &right_map)), map_expected);
+        }
+    }
+}
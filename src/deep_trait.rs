@@ -1,4 +1,18 @@
-use std::{fmt, ops::Add};
+use std::{collections::BTreeMap, fmt, ops::Add};
+
+use crate::{
+    container::{self, Container},
+    geometry,
+    outline::{self, Border, Outline},
+    units::{Meter, Millimeter, Second, Unit},
+};
+
+/// 可以放进 [`Cache`](crate::cache::Cache) 的条目，`Address` 是条目的地址，缓存按地址的字节计算哈希。
+pub trait CacheableItem: Clone + Default + fmt::Debug {
+    type Address: AsRef<[u8]> + Clone + fmt::Debug + Eq;
+    // 空条目不会被放进缓存
+    fn is_null(&self) -> bool;
+}
 
 pub fn deep_trait() {
     // 关联类型
@@ -25,55 +39,44 @@ pub fn deep_trait() {
     println!("c next: {:?}", c.next());
 
     // 为了代码的可读性，当你使用了泛型后，你需要在所有地方都写 Iterator<Item>，而使用了关联类型，你只需要写 Iterator，当类型定义复杂时，这种写法可以极大的增加可读性：
-    pub trait CacheableItem: Clone + Default + fmt::Debug {
-        type Address: AsRef<[u8]> + Clone + fmt::Debug + Eq;
-        fn is_null(&self) -> bool;
-    }
-
-    // 例如上面的代码，Address 的写法自然远比 AsRef<[u8]> + Clone + fmt::Debug + Eq + Hash 要简单的多，而且含义清晰。
-    // 再例如，如果使用泛型，你将得到以下的代码：
-    trait Container<A, B> {
-        fn contains(&self, a: A, b: B) -> bool;
-    }
-
-    fn difference<A, B, C>(container: &C) -> i32
-    where
-        C: Container<A, B>,
-    {
-        1
-    }
-
-    // 可以看到，由于使用了泛型，导致函数头部也必须增加泛型的声明，而使用关联类型，将得到可读性好得多的代码：
-    trait Container1 {
-        type A;
-        type B;
-        fn contains(&self, a: Self::A, b: Self::B) -> bool;
-    }
-
-    fn difference1<C: Container1>(container: &C) {}
-
-    trait Container2 {
-        type A;
-        type B;
-        fn contains(&self, a: &Self::A, b: &Self::B);
-    }
-
-    struct ContainerExp<A, B> {
-        a: A,
-        b: B,
-    }
-
-    impl<A, B> Container2 for ContainerExp<A, B> {
-        type A = u32;
-        type B = u32;
-        fn contains(&self, a: &Self::A, b: &Self::B) {
-            println!("A: {}, B: {}", a, b);
-        }
-    }
-
-    // todo: add example for this
-    let container_exp_1 = ContainerExp { a: 1, b: 1 };
-    container_exp_1.contains(&container_exp_1.a, &container_exp_1.b);
+    // 例如模块顶层的 CacheableItem(cache 模块基于它实现了一个通用的缓存)，Address 的写法自然远比 AsRef<[u8]> + Clone + fmt::Debug + Eq + Hash 要简单的多，而且含义清晰。
+    // 再例如 container 模块中的容器特征，如果使用泛型，你将得到以下的代码(GenericContainer 和 difference_generic)：
+    // trait GenericContainer<A, B> {
+    //     fn contains(&self, a: &A, b: &B) -> bool;
+    // }
+    //
+    // fn difference_generic<'a, A, B, L, R>(left: &'a L, right: &R) -> Vec<(&'a A, &'a B)>
+    // where
+    //     L: GenericContainer<A, B>,
+    //     R: GenericContainer<A, B>,
+
+    // 可以看到，由于使用了泛型，导致函数头部也必须增加泛型的声明，而使用关联类型，将得到可读性好得多的代码(Container 和 difference)：
+    // trait Container {
+    //     type A;
+    //     type B;
+    //     fn contains(&self, a: &Self::A, b: &Self::B) -> bool;
+    // }
+    //
+    // fn difference<'a, L, R>(left: &'a L, right: &R) -> Vec<(&'a L::A, &'a L::B)>
+    // where
+    //     L: Container,
+    //     R: Container<A = L::A, B = L::B>,
+
+    // 自定义的类型只需要实现一次 Container，例如把 (A, B) 对拆成两列保存的 Pairs
+    let mut pairs = container::Pairs::new();
+    pairs.push(1u32, 1u32);
+    println!(
+        "pairs contains (1, 1): {}",
+        Container::contains(&pairs, &1, &1)
+    );
+    // difference 是两个关联类型相同的容器之间的集合差，两个容器的类型可以不同
+    let stock = vec![("apple", 3), ("pear", 0), ("plum", 7)];
+    let sold_out = BTreeMap::from([("pear", 0)]);
+    println!("in stock: {:?}", container::difference(&stock, &sold_out));
+    println!(
+        "in stock: {:?}",
+        container::difference_generic(&stock, &sold_out)
+    );
 
     // 默认泛型类型参数
     // 当使用泛型类型参数时，可以为其指定一个默认的具体类型，例如标准库中的 std::ops::Add 特征：
@@ -84,6 +87,7 @@ pub fn deep_trait() {
     // }
     // 它有一个泛型参数 RHS，但是与我们以往的用法不同，这里它给 RHS 一个默认值，也就是当用户不指定 RHS 时，默认使用两个同样类型的值进行相加，然后返回一个关联类型 Output。
 
+    // 这里要演示如何为自己的类型实现 Add，所以保留一个局部的 Point；实际使用的点和向量见 geometry::Point
     #[derive(Debug, PartialEq)]
     struct Point {
         x: i32,
@@ -134,6 +138,11 @@ pub fn deep_trait() {
     // 因为是为 Millimeters 实现的 Add 操作, 所以做加法时候 Millimeters
     let final_millimeter = millimeter + meter;
     println!("{:?}", final_millimeter);
+    // units 模块把单位做成了零大小的标记类型：Millimeter + Meter 会先把右侧精确换算成毫米，
+    // 而 Meter + Second 这种量纲不同的加法、Meter + Millimeter 这种会丢失精度的加法都无法通过编译
+    let total = Millimeter::of(10) + Meter::of(5);
+    let speed = Meter::of(100.0) / Second::of(8.0);
+    println!("{}, {}", total, speed);
 
     // 调用同名的方法
     // 不同特征拥有同名的方法是很正常的事情，你没有任何办法阻止这一点；甚至除了特征上的同名方法外，在你的类型上，也有同名方法：
@@ -208,7 +217,8 @@ pub fn deep_trait() {
     trait OutlinePrint: Display {
         fn outline_print(&self) {
             let output = self.to_string();
-            let len = output.len();
+            // len() 是字节数，中文字符占 3 个字节却只占 2 列，需要按显示宽度计算
+            let len = outline::display_width(&output);
             println!("{}", "*".repeat(len + 4));
             println!("*{}*", " ".repeat(len + 2));
             println!("* {} *", output);
@@ -217,15 +227,16 @@ pub fn deep_trait() {
         }
     }
 
-    impl fmt::Display for Point {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(f, "({}, {})", self.x, self.y)
-        }
-    }
-
-    impl OutlinePrint for Point {}
-    let c = Point { x: 1, y: 2 };
+    // geometry::Point 已经实现了 Display，输出 (x, y)，因此满足 OutlinePrint 的约束
+    impl OutlinePrint for geometry::Point<i32> {}
+    let c = geometry::Point::new(1, 2);
     c.outline_print();
+    // outline 模块把它做成了完整的文本框：支持多行、标题和多种边框，并且为所有 T: Display 提供了通用实现
+    let card = Outline::new()
+        .border(Border::Rounded)
+        .title("坐标")
+        .render(&format!("c = {}\n第一象限", c));
+    println!("{}", card);
 
     // 在外部类型上实现外部特征(newtype)
     // 这里提供一个办法来绕过孤儿规则，那就是使用newtype 模式，简而言之：就是为一个元组结构体创建新类型。该元组结构体封装有一个字段，该字段就是希望实现特征的具体类型。
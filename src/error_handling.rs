@@ -1,4 +1,10 @@
-use std::fmt::{self, Debug, Display};
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    fs, io,
+    num::{IntErrorKind, ParseIntError},
+    path::Path,
+};
 
 pub fn error_handling() {
     // 在之前的返回值和错误处理章节中，我们学习了几个重要的概念，例如 Result 用于返回结果处理，? 用于错误的传播，若大家对此还较为模糊，强烈建议回头温习下。
@@ -218,30 +224,285 @@ pub fn error_handling() {
 
     // 最简单的错误
     // AppError 是自定义错误类型，它可以是当前包中定义的任何类型，在这里为了简化，我们使用了单元结构体作为例子。
-    // 为 AppError 自动派生 Debug 特征
-    #[derive(Debug)]
-    struct AppError;
-
-    // 为 AppError 实现 std::fmt::Display 特征
-    impl Display for AppError {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(f, "An Error Occurred, Please Try Again!") // user-facing output
+    // 放在单独的代码块中，避免和下面模块级的 AppError 重名
+    {
+        // 为 AppError 自动派生 Debug 特征
+        #[derive(Debug)]
+        struct AppError;
+
+        // 为 AppError 实现 std::fmt::Display 特征
+        impl Display for AppError {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "An Error Occurred, Please Try Again!") // user-facing output
+            }
+        }
+
+        // 一个示例函数用于产生 AppError 错误
+        fn produce_error() -> Result<(), AppError> {
+            Err(AppError)
         }
-    }
 
-    // 一个示例函数用于产生 AppError 错误
-    fn produce_error() -> Result<(), AppError> {
-        Err(AppError)
+        match produce_error() {
+            Err(e) => eprintln!("{}", e),
+            _ => println!("No error"),
+        }
+
+        eprintln!("print = {:?}", produce_error()); // Err({ file: src/main.rs, line: 17 })
     }
 
-    match produce_error() {
-        Err(e) => eprintln!("{}", e),
-        _ => println!("No error"),
+    // 更详尽的错误
+    // 单元结构体只能告诉用户"出错了"，真实的项目中往往要区分错误的种类，并保留底层的错误作为原因(source)。
+    // 模块级的 AppError 是一个枚举：Io、Parse、Validation 分别对应 I/O、整数解析和校验失败，
+    // 通过实现 From<io::Error> 和 From<ParseIntError>，? 就可以把底层错误自动转换成 AppError：
+    match load_port("no/such/port.conf") {
+        Ok(port) => println!("port = {}", port),
+        Err(e) => println!("{}", report(&e)),
     }
 
-    eprintln!("print = {:?}", produce_error()); // Err({ file: src/main.rs, line: 17 })
+    let e = parse_port("70000").unwrap_err();
+    println!("{} ({})", e, e.code());
+    let e = AppError::validation_caused_by("port", "must be a valid TCP port", e);
+    println!("{}", report(&e));
 
     // 上面的代码中除了实现 From 外，还有一点特别重要，那就是 ? 可以将错误进行隐式的强制转换：File::open 返回的是 std::io::Error， 我们并没有进行任何显式的转换，它就能自动变成 AppError ，这就是 ? 的强大之处！
     // 总结
     // Rust 一个为人津津乐道的点就是强大、易用的错误处理，对于新手来说，这个机制可能会有些复杂，但是一旦体会到了其中的好处，你将跟我一样沉醉其中不能自拔。
 }
+
+/// 应用程序的错误类型，区分 I/O、整数解析和校验三类失败，底层错误通过 [`Error::source`] 暴露。
+///
+/// 按照标准库的约定，Display 只描述当前这一层，不重复 source 的内容，完整的原因链交给 [`report`] 输出。
+///
+/// ```
+/// use rust_thinking::error_handling::{parse_port, AppError};
+///
+/// let e = parse_port("http").unwrap_err();
+/// assert!(matches!(e, AppError::Parse(_)));
+/// assert_eq!(e.code(), "E0202");
+/// ```
+#[derive(Debug)]
+pub enum AppError {
+    Io(io::Error),
+    Parse(ParseIntError),
+    Validation {
+        field: &'static str,
+        message: String,
+        source: Option<Box<AppError>>,
+    },
+}
+
+impl AppError {
+    pub fn validation(field: &'static str, message: impl Into<String>) -> AppError {
+        AppError::Validation {
+            field,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    // 只有校验错误有放原因的位置(Io 和 Parse 的 source 就是它们包装的底层错误)，所以原因只能在构造校验错误时给出
+    pub fn validation_caused_by(
+        field: &'static str,
+        message: impl Into<String>,
+        cause: impl Into<AppError>,
+    ) -> AppError {
+        AppError::Validation {
+            field,
+            message: message.into(),
+            source: Some(Box::new(cause.into())),
+        }
+    }
+
+    /// 稳定的错误码，可以写进文档、日志，供脚本匹配，已经发布的错误码不允许修改含义。
+    ///
+    /// | 错误码 | 含义 |
+    /// | --- | --- |
+    /// | E0100 | 其它 I/O 错误 |
+    /// | E0101 | 文件不存在 |
+    /// | E0102 | 没有权限 |
+    /// | E0200 | 其它解析错误 |
+    /// | E0201 | 输入为空 |
+    /// | E0202 | 包含非法字符 |
+    /// | E0203 | 数值超出范围 |
+    /// | E0300 | 校验失败 |
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Io(e) => match e.kind() {
+                io::ErrorKind::NotFound => "E0101",
+                io::ErrorKind::PermissionDenied => "E0102",
+                _ => "E0100",
+            },
+            AppError::Parse(e) => match e.kind() {
+                IntErrorKind::Empty => "E0201",
+                IntErrorKind::InvalidDigit => "E0202",
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => "E0203",
+                _ => "E0200",
+            },
+            AppError::Validation { .. } => "E0300",
+        }
+    }
+}
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::Io(_) => write!(f, "I/O operation failed"),
+            AppError::Parse(_) => write!(f, "invalid integer"),
+            AppError::Validation { field, message, .. } => {
+                write!(f, "invalid value for `{}`: {}", field, message)
+            }
+        }
+    }
+}
+
+impl Error for AppError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AppError::Io(e) => Some(e),
+            AppError::Parse(e) => Some(e),
+            AppError::Validation { source, .. } => source.as_deref().map(|e| e as _),
+        }
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> AppError {
+        AppError::Io(e)
+    }
+}
+
+impl From<ParseIntError> for AppError {
+    fn from(e: ParseIntError) -> AppError {
+        AppError::Parse(e)
+    }
+}
+
+// ? 会通过 From<ParseIntError> 把解析错误转换成 AppError
+pub fn parse_port(s: &str) -> Result<u16, AppError> {
+    let port: u16 = s.trim().parse()?;
+    if port == 0 {
+        return Err(AppError::validation("port", "must not be 0"));
+    }
+    Ok(port)
+}
+
+// 读取文件时的 io::Error 同样由 ? 自动转换，解析失败则包装成带原因的校验错误
+pub fn load_port(path: impl AsRef<Path>) -> Result<u16, AppError> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path)?;
+    parse_port(&text).map_err(|e| match e {
+        AppError::Parse(_) => AppError::validation_caused_by(
+            "port",
+            format!("{} does not contain a port number", path.display()),
+            e,
+        ),
+        e => e,
+    })
+}
+
+/// 输出错误以及完整的原因链，AppError 会在第一行带上错误码：
+///
+/// ```text
+/// error[E0300]: invalid value for `port`: port.conf does not contain a port number
+/// caused by:
+///     1: invalid integer
+///     2: number too large to fit in target type
+/// ```
+pub fn report(err: &(dyn Error + 'static)) -> String {
+    let mut out = match err.downcast_ref::<AppError>() {
+        Some(e) => format!("error[{}]: {}", e.code(), e),
+        None => format!("error: {}", err),
+    };
+    let mut cause = err.source();
+    if cause.is_some() {
+        out.push_str("\ncaused by:");
+    }
+    let mut n = 1;
+    while let Some(e) = cause {
+        out.push_str(&format!("\n    {}: {}", n, e));
+        cause = e.source();
+        n += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn question_mark_converts_errors() {
+        assert_eq!(parse_port(" 8080\n").unwrap(), 8080);
+        assert!(matches!(parse_port("").unwrap_err(), AppError::Parse(_)));
+        assert!(matches!(
+            parse_port("0").unwrap_err(),
+            AppError::Validation { field: "port", .. }
+        ));
+        match load_port("definitely/missing/port.conf").unwrap_err() {
+            AppError::Io(e) => assert_eq!(e.kind(), io::ErrorKind::NotFound),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn codes_are_stable() {
+        let cases = [
+            ("", "E0201"),
+            ("8o", "E0202"),
+            ("65536", "E0203"),
+            ("-1", "E0202"),
+            ("0", "E0300"),
+        ];
+        for (input, code) in cases {
+            assert_eq!(parse_port(input).unwrap_err().code(), code, "{:?}", input);
+        }
+        let denied = AppError::from(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert_eq!(denied.code(), "E0102");
+        let other = AppError::from(io::Error::other("disk on fire"));
+        assert_eq!(other.code(), "E0100");
+    }
+
+    #[test]
+    fn source_chain_reaches_the_root_cause() {
+        let path = env::temp_dir().join(format!("rust_thinking_port_{}", std::process::id()));
+        fs::write(&path, "70000").unwrap();
+        let err = load_port(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        let parse = err.source().unwrap();
+        assert!(matches!(
+            parse.downcast_ref::<AppError>(),
+            Some(AppError::Parse(_))
+        ));
+        let root = parse.source().unwrap();
+        assert_eq!(
+            root.downcast_ref::<ParseIntError>().unwrap().kind(),
+            &IntErrorKind::PosOverflow
+        );
+        assert!(root.source().is_none());
+
+        let report = report(&err);
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines[0].starts_with("error[E0300]: invalid value for `port`: "));
+        assert_eq!(
+            &lines[1..],
+            [
+                "caused by:",
+                "    1: invalid integer",
+                "    2: number too large to fit in target type",
+            ]
+        );
+    }
+
+    #[test]
+    fn report_without_sources() {
+        let err = AppError::validation("name", "must not be empty");
+        assert_eq!(
+            report(&err),
+            "error[E0300]: invalid value for `name`: must not be empty"
+        );
+        let plain = io::Error::other("boom");
+        assert_eq!(report(&plain), "error: boom");
+    }
+}
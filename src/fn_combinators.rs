@@ -0,0 +1,330 @@
+use std::{cell::Cell, hash::Hash};
+
+use crate::memo::Memo;
+
+// 闭包组合器
+// closure::factory 演示了把闭包装箱后作为返回值，这里把"接收闭包、返回闭包"的模式整理成一组组合器。
+// closure 章节讲过三种 Fn 特征的关系：Fn 可以当作 FnMut 使用，FnMut 可以当作 FnOnce 使用，反过来则不行。
+// 每个组合器的文档都写明了它对参数要求的特征，以及返回的闭包实现的特征：
+//
+// | 组合器    | 参数要求             | 返回值实现 |
+// | --------- | -------------------- | ---------- |
+// | compose   | Fn + Fn              | Fn         |
+// | partial   | Fn，首个参数 Clone   | Fn         |
+// | memoize   | Fn                   | FnMut      |
+// | once      | FnOnce               | Fn         |
+// | debounce  | FnMut                | FnMut      |
+// | Pipeline  | Fn / FnMut / FnOnce  | run 需要 &mut self |
+
+/// 函数组合：先执行 `f`，再执行 `g`，参数和返回值都要求 `Fn`。
+pub use crate::function::compose;
+
+/// 把值依次传给一串函数，`pipe!(x, f, g, h)` 等价于 `h(g(f(x)))`。
+///
+/// ```
+/// use rust_thinking::pipe;
+///
+/// let n = pipe!("  42 ", str::trim, |s: &str| s.parse::<i32>().unwrap(), |n| n * 2);
+/// assert_eq!(n, 84);
+/// assert_eq!(pipe!(5), 5);
+/// ```
+#[macro_export]
+macro_rules! pipe {
+    ($value:expr $(,)?) => {
+        $value
+    };
+    ($value:expr, $f:expr $(, $rest:expr)* $(,)?) => {
+        $crate::pipe!(($f)($value) $(, $rest)*)
+    };
+}
+
+/// 偏应用：固定 `f` 的第一个参数，得到只接收第二个参数的闭包。
+///
+/// 要求 `f: Fn`，因为返回的闭包可能被调用多次；`a` 每次调用都会 clone 一份传给 `f`。返回的闭包实现 `Fn`。
+///
+/// ```
+/// use rust_thinking::fn_combinators::partial;
+///
+/// let greet = partial(|greeting: String, name: &str| format!("{}, {}!", greeting, name), "Hello".to_string());
+/// assert_eq!(greet("Rust"), "Hello, Rust!");
+/// assert_eq!(greet("world"), "Hello, world!");
+/// ```
+pub fn partial<A: Clone, B, C>(f: impl Fn(A, B) -> C, a: A) -> impl Fn(B) -> C {
+    move |b| f(a.clone(), b)
+}
+
+/// 缓存 `f` 的结果，同一个参数只计算一次，内部使用 [`Memo`]。
+///
+/// 要求 `f: Fn`；返回的闭包需要修改缓存，因此只实现 `FnMut`。
+///
+/// ```
+/// use rust_thinking::fn_combinators::memoize;
+///
+/// let mut slow_square = memoize(|x: u64| x * x);
+/// assert_eq!(slow_square(12), 144);
+/// assert_eq!(slow_square(12), 144);
+/// ```
+///
+/// 返回的闭包不是 `Fn`，不能传给要求 `Fn` 的函数：
+///
+/// ```compile_fail,E0277
+/// use rust_thinking::fn_combinators::memoize;
+///
+/// fn call_twice(f: impl Fn(u64) -> u64) -> u64 {
+///     f(1) + f(2)
+/// }
+///
+/// call_twice(memoize(|x: u64| x * x));
+/// ```
+pub fn memoize<A, R>(f: impl Fn(A) -> R) -> impl FnMut(A) -> R
+where
+    A: Hash + Eq + Clone,
+    R: Clone,
+{
+    let mut memo = Memo::new(move |a: &A| f(a.clone()));
+    move |a| memo.get(a)
+}
+
+/// 把只能调用一次的 `FnOnce` 变成可以反复调用的 `Fn`：第一次调用执行 `f` 并返回 `Some`，之后的调用直接返回 `None`。
+///
+/// 闭包被放在 `Cell<Option<_>>` 中作为守卫，调用时 `take` 出来，因此返回的闭包只需要 `&self`，实现 `Fn`。
+///
+/// ```
+/// use rust_thinking::fn_combinators::once;
+///
+/// let name = String::from("init");
+/// let init = once(move |suffix: &str| name + suffix);
+/// assert_eq!(init("!"), Some("init!".to_string()));
+/// assert_eq!(init("?"), None);
+/// ```
+pub fn once<A, R>(f: impl FnOnce(A) -> R) -> impl Fn(A) -> Option<R> {
+    let guard = Cell::new(Some(f));
+    move |a| guard.take().map(|f| f(a))
+}
+
+/// 按次数去抖：每累计 `n` 次调用才真正执行一次 `f`(使用最后一次调用的参数)，其余调用返回 `None`。
+///
+/// 要求 `f: FnMut`；返回的闭包需要修改计数器，实现 `FnMut`。
+///
+/// ```
+/// use rust_thinking::fn_combinators::debounce;
+///
+/// let mut save = debounce(3, |text: &str| text.len());
+/// assert_eq!(save("a"), None);
+/// assert_eq!(save("ab"), None);
+/// assert_eq!(save("abc"), Some(3));
+/// assert_eq!(save("abcd"), None);
+/// ```
+pub fn debounce<A, R>(n: usize, mut f: impl FnMut(A) -> R) -> impl FnMut(A) -> Option<R> {
+    assert!(n > 0, "debounce count must be positive");
+    let mut calls = 0;
+    move |a| {
+        calls += 1;
+        if calls == n {
+            calls = 0;
+            Some(f(a))
+        } else {
+            None
+        }
+    }
+}
+
+enum Stage<'a, T> {
+    Fn(Box<dyn Fn(T) -> T + 'a>),
+    FnMut(Box<dyn FnMut(T) -> T + 'a>),
+    // 执行一次之后变为 None
+    FnOnce(Option<Box<dyn FnOnce(T) -> T + 'a>>),
+}
+
+/// 由装箱闭包组成的处理流水线，三种 Fn 特征的闭包都可以作为其中的一个阶段。
+///
+/// `FnOnce` 阶段只会在第一次 `run` 时执行，之后的 `run` 会跳过它。
+///
+/// ```
+/// use rust_thinking::fn_combinators::Pipeline;
+///
+/// let mut seen = Vec::new();
+/// let banner = String::from("v1: ");
+/// let mut pipeline = Pipeline::new()
+///     .then(|s: String| s.trim().to_string())
+///     .then_mut(|s: String| {
+///         seen.push(s.clone());
+///         s
+///     })
+///     .then_once(move |s: String| banner + &s);
+///
+/// assert_eq!(pipeline.run("  first ".to_string()), "v1: first");
+/// assert_eq!(pipeline.run(" second".to_string()), "second");
+/// drop(pipeline);
+/// assert_eq!(seen, ["first", "second"]);
+/// ```
+pub struct Pipeline<'a, T> {
+    stages: Vec<Stage<'a, T>>,
+}
+
+impl<'a, T> Pipeline<'a, T> {
+    pub fn new() -> Pipeline<'a, T> {
+        Pipeline { stages: Vec::new() }
+    }
+
+    pub fn then(mut self, f: impl Fn(T) -> T + 'a) -> Pipeline<'a, T> {
+        self.stages.push(Stage::Fn(Box::new(f)));
+        self
+    }
+
+    pub fn then_mut(mut self, f: impl FnMut(T) -> T + 'a) -> Pipeline<'a, T> {
+        self.stages.push(Stage::FnMut(Box::new(f)));
+        self
+    }
+
+    pub fn then_once(mut self, f: impl FnOnce(T) -> T + 'a) -> Pipeline<'a, T> {
+        self.stages.push(Stage::FnOnce(Some(Box::new(f))));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    // FnMut 阶段需要 &mut，FnOnce 阶段需要把闭包取出来，所以 run 需要 &mut self
+    pub fn run(&mut self, input: T) -> T {
+        self.stages
+            .iter_mut()
+            .fold(input, |value, stage| match stage {
+                Stage::Fn(f) => f(value),
+                Stage::FnMut(f) => f(value),
+                Stage::FnOnce(f) => match f.take() {
+                    Some(f) => f(value),
+                    None => value,
+                },
+            })
+    }
+}
+
+impl<'a, T> Default for Pipeline<'a, T> {
+    fn default() -> Pipeline<'a, T> {
+        Pipeline::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    // 这些函数只在编译期检查闭包实现了哪种 Fn 特征
+    fn is_fn<A, R>(_: &impl Fn(A) -> R) {}
+    fn is_fn_mut<A, R>(_: &impl FnMut(A) -> R) {}
+
+    #[test]
+    fn compose_and_partial_produce_fn() {
+        let parse_then_double = compose(|s: &str| s.parse::<i32>().unwrap(), |n| n * 2);
+        is_fn(&parse_then_double);
+        assert_eq!(parse_then_double("21"), 42);
+
+        let add = |a: Vec<i32>, b: i32| a.iter().sum::<i32>() + b;
+        let add_to_base = partial(add, vec![1, 2, 3]);
+        is_fn(&add_to_base);
+        assert_eq!(add_to_base(4), 10);
+        assert_eq!(add_to_base(0), 6);
+    }
+
+    #[test]
+    fn pipe_applies_left_to_right() {
+        let trace = RefCell::new(Vec::new());
+        let step = |name: &'static str| {
+            let trace = &trace;
+            move |x: i32| {
+                trace.borrow_mut().push(name);
+                x + 1
+            }
+        };
+        assert_eq!(pipe!(0, step("a"), step("b"), step("c")), 3);
+        assert_eq!(*trace.borrow(), ["a", "b", "c"]);
+        assert_eq!(pipe!(2, |x| x * 10, |n: i32| n.to_string(),), "20");
+    }
+
+    #[test]
+    fn memoize_requires_fn_and_produces_fn_mut() {
+        let calls = Cell::new(0);
+        let f = |x: u32| {
+            calls.set(calls.get() + 1);
+            x + 1
+        };
+        is_fn(&f);
+        let mut cached = memoize(f);
+        is_fn_mut(&cached);
+        assert_eq!((cached(1), cached(2), cached(1), cached(2)), (2, 3, 2, 3));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn once_turns_fn_once_into_fn() {
+        let v = vec![1, 2, 3];
+        // 闭包把 v 移出，只实现了 FnOnce
+        let consume = move |extra: i32| {
+            let mut v = v;
+            v.push(extra);
+            v
+        };
+        // consume 不是 Fn，见 tests/compile_fail/fn_combinators_fn_once_is_not_fn.rs
+        let guarded = once(consume);
+        is_fn(&guarded);
+        assert_eq!(guarded(4), Some(vec![1, 2, 3, 4]));
+        assert_eq!(guarded(5), None);
+        assert_eq!(guarded(6), None);
+    }
+
+    #[test]
+    fn debounce_fires_every_nth_call() {
+        let mut fired = Vec::new();
+        {
+            let record = |x: i32| {
+                fired.push(x);
+                x
+            };
+            is_fn_mut(&record);
+            let mut debounced = debounce(2, record);
+            is_fn_mut(&debounced);
+            let results: Vec<Option<i32>> = (1..=5).map(&mut debounced).collect();
+            assert_eq!(results, [None, Some(2), None, Some(4), None]);
+        }
+        assert_eq!(fired, [2, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "debounce count must be positive")]
+    fn debounce_rejects_zero() {
+        let _ = debounce(0, |x: i32| x);
+    }
+
+    #[test]
+    fn pipeline_mixes_all_three_fn_traits() {
+        let mut log = Vec::new();
+        let offset = 100;
+        let token = String::from("once");
+        let mut pipeline = Pipeline::new()
+            .then(move |x: i64| x + offset)
+            .then_mut(|x| {
+                log.push(x);
+                x * 2
+            })
+            .then_once(move |x| {
+                drop(token);
+                -x
+            });
+        assert_eq!(pipeline.len(), 3);
+        assert_eq!(pipeline.run(1), -202);
+        assert_eq!(pipeline.run(2), 204);
+        drop(pipeline);
+        assert_eq!(log, [101, 102]);
+
+        let mut empty: Pipeline<'_, u8> = Pipeline::default();
+        assert!(empty.is_empty());
+        assert_eq!(empty.run(7), 7);
+    }
+}
@@ -1 +1,329 @@
-pub fn format_output() {}
+use std::fmt::{self, Alignment, Write};
+
+use crate::{geometry, outline::display_width};
+
+pub fn format_output() {
+    // 格式化输出
+    // println!/format!/write! 的占位符格式为 {参数:填充 对齐 符号 # 0 宽度 .精度 类型}，除了参数之外都可以省略
+    let world = "world";
+    println!("Hello {}", world); // {} 使用 Display 特征
+    println!("{:?}", vec![1, 2, 3]); // {:?} 使用 Debug 特征
+    println!("{:#?}", (1, "a")); // {:#?} 是 Debug 的美化输出，会换行和缩进
+
+    // 位置参数与具名参数
+    let (a, b) = ("a", "b");
+    println!("{1} {0} {1}", a, b);
+    let name = "rust";
+    println!("{name} {age}", age = 10); // 可以直接捕获作用域中的变量
+
+    // 宽度：字符串默认左对齐，数字默认右对齐
+    assert_eq!(format!("[{:5}]", "x"), "[x    ]");
+    assert_eq!(format!("[{:5}]", 1), "[    1]");
+    // 宽度也可以通过参数传入
+    assert_eq!(format!("[{:1$}]", "x", 3), "[x  ]");
+    assert_eq!(format!("[{:width$}]", "x", width = 3), "[x  ]");
+
+    // 填充与对齐：< 左对齐，^ 居中，> 右对齐，对齐符号前面的字符就是填充字符
+    assert_eq!(format!("[{:*<5}]", "x"), "[x****]");
+    assert_eq!(format!("[{:-^5}]", "x"), "[--x--]");
+    assert_eq!(format!("[{:>5}]", "x"), "[    x]");
+    // 0 是数字专用的填充方式，会把 0 填充在符号之后
+    assert_eq!(format!("{:05}", -5), "-0005");
+
+    // 符号：+ 表示总是输出正负号
+    assert_eq!(format!("{:+}", 5), "+5");
+
+    // 精度：对浮点数是保留的小数位数，对字符串则是截断长度
+    assert_eq!(format!("{:.2}", 1.2345), "1.23");
+    assert_eq!(format!("{:+.1}", 3.0), "+3.0");
+    assert_eq!(format!("{:.3}", "abcdef"), "abc");
+    assert_eq!(format!("{:.*}", 1, 2.55), "2.5"); // 精度通过参数传入
+
+    // 进制与 # 号：# 会加上 0x、0o、0b 这样的前缀
+    assert_eq!(format!("{:x} {:#x} {:#X}", 255, 255, 255), "ff 0xff 0xFF");
+    assert_eq!(
+        format!("{:#b} {:o} {:#010b}", 5, 8, 5),
+        "0b101 10 0b00000101"
+    );
+    // 科学计数法
+    assert_eq!(format!("{:e}", 1234.5), "1.2345e3");
+
+    // 为自定义类型实现 Display/Debug 时，上面这些参数都保存在 Formatter 中：
+    // f.width()、f.fill()、f.align()、f.precision()、f.sign_plus()、f.alternate()，需要由实现者自己决定如何处理，
+    // 直接 write!(f, ...) 会忽略它们，所以 geometry::Point 的 Display 借助 pad_aligned 来处理宽度和对齐。
+    let p = geometry::Point::new(1.0, -2.5);
+    println!("[{}]", p);
+    println!("[{:+.2}]", p);
+    println!("[{:#}]", p);
+    println!("[{:*^20.1}]", p);
+    println!("{:?}", p);
+    println!("{:.1?}", p); // debug_struct 会把精度等参数传递给每个字段
+    println!("{:#?}", p);
+
+    let bytes = HexBytes(&[0xde, 0xad, 0xbe, 0xef]);
+    println!("[{}] [{:#.1}] [{:.2}] [{:>12}]", bytes, bytes, bytes, bytes);
+
+    // 用 Table 把占位符的效果整理成一张表
+    let specs: Vec<Vec<String>> = vec![
+        vec!["spec".into(), "value".into(), "output".into()],
+        vec!["{:>8}".into(), "42".into(), format!("{:>8}", 42)],
+        vec!["{:*^8}".into(), "\"mid\"".into(), format!("{:*^8}", "mid")],
+        vec![
+            "{:+.3}".into(),
+            "PI".into(),
+            format!("{:+.3}", std::f64::consts::PI),
+        ],
+        vec!["{:#x}".into(), "255".into(), format!("{:#x}", 255)],
+        vec![
+            "{:08.2}".into(),
+            "-12.3456".into(),
+            format!("{:08.2}", -12.3456),
+        ],
+    ];
+    let table = Table::new(specs)
+        .header()
+        .align(1, Alignment::Right)
+        .align(2, Alignment::Right);
+    print!("{}", table);
+}
+
+/// 按照 Formatter 中的宽度、填充字符和对齐方式输出 `s`，没有指定对齐方式时使用 `default`。
+///
+/// 与 `Formatter::pad` 不同，这里不会用精度去截断 `s`，精度留给调用方按自己的语义处理；
+/// 宽度和 [`Table`] 一样按显示宽度计算，中文等宽字符占两列。
+pub fn pad_aligned(f: &mut fmt::Formatter, s: &str, default: Alignment) -> fmt::Result {
+    let len = display_width(s);
+    let width = f.width().unwrap_or(0);
+    if len >= width {
+        return f.write_str(s);
+    }
+
+    let padding = width - len;
+    let (before, after) = match f.align().unwrap_or(default) {
+        Alignment::Left => (0, padding),
+        Alignment::Right => (padding, 0),
+        Alignment::Center => (padding / 2, padding - padding / 2),
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    f.write_str(s)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+/// 以十六进制输出字节序列：`#` 输出紧凑的 `0x` 前缀形式，精度限制最多输出的字节数。
+///
+/// ```
+/// use rust_thinking::format_output::HexBytes;
+///
+/// let bytes = HexBytes(&[0xde, 0xad, 0xbe, 0xef]);
+/// assert_eq!(format!("{}", bytes), "de ad be ef");
+/// assert_eq!(format!("{:#}", bytes), "0xdeadbeef");
+/// assert_eq!(format!("{:.2}", bytes), "de ad ..");
+/// ```
+pub struct HexBytes<'a>(pub &'a [u8]);
+
+impl fmt::Display for HexBytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let shown = f.precision().unwrap_or(self.0.len()).min(self.0.len());
+        let mut s = String::new();
+        if f.alternate() {
+            s.push_str("0x");
+        }
+        for (i, b) in self.0[..shown].iter().enumerate() {
+            if i > 0 && !f.alternate() {
+                s.push(' ');
+            }
+            write!(s, "{:02x}", b)?;
+        }
+        if shown < self.0.len() {
+            s.push_str(if f.alternate() { ".." } else { " .." });
+        }
+        pad_aligned(f, &s, Alignment::Left)
+    }
+}
+
+/// 把 `Vec<Vec<String>>` 排版成按列对齐的表格，每一列的宽度取该列最长的单元格，
+/// 行的长度不一致时缺少的单元格按空字符串处理。
+///
+/// ```
+/// use std::fmt::Alignment;
+/// use rust_thinking::format_output::Table;
+///
+/// let rows = vec![
+///     vec!["name".to_string(), "size".to_string()],
+///     vec!["sneaker".to_string(), "10".to_string()],
+///     vec!["boot".to_string(), "9".to_string()],
+/// ];
+/// let table = Table::new(rows).header().align(1, Alignment::Right);
+/// assert_eq!(
+///     table.to_string(),
+///     "name    | size\n\
+///      --------+-----\n\
+///      sneaker |   10\n\
+///      boot    |    9\n"
+/// );
+/// ```
+pub struct Table {
+    rows: Vec<Vec<String>>,
+    aligns: Vec<Alignment>,
+    header: bool,
+}
+
+impl Table {
+    pub fn new(rows: Vec<Vec<String>>) -> Table {
+        Table {
+            rows,
+            aligns: Vec::new(),
+            header: false,
+        }
+    }
+
+    // 把第一行当作表头，表头下面会输出一条分隔线
+    pub fn header(mut self) -> Table {
+        self.header = true;
+        self
+    }
+
+    // 设置某一列的对齐方式，没有设置的列默认左对齐
+    pub fn align(mut self, column: usize, align: Alignment) -> Table {
+        if self.aligns.len() <= column {
+            self.aligns.resize(column + 1, Alignment::Left);
+        }
+        self.aligns[column] = align;
+        self
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let columns = self.rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        (0..columns)
+            .map(|c| {
+                self.rows
+                    .iter()
+                    .filter_map(|r| r.get(c))
+                    .map(|cell| display_width(cell))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let widths = self.widths();
+        for (i, row) in self.rows.iter().enumerate() {
+            let mut line = String::new();
+            // 行尾只去掉这里补的空格，单元格内容本身末尾的空格要保留
+            let mut end = 0;
+            for (c, width) in widths.iter().enumerate() {
+                if c > 0 {
+                    line.push_str(" |");
+                    end = line.len();
+                    line.push(' ');
+                }
+                let cell = row.get(c).map(String::as_str).unwrap_or("");
+                // {:<width$} 按字符数填充，中文等宽字符占两列，所以按显示宽度手动补空格
+                let gap = width - display_width(cell);
+                let (left, right) = match self.aligns.get(c).copied().unwrap_or(Alignment::Left) {
+                    Alignment::Left => (0, gap),
+                    Alignment::Right => (gap, 0),
+                    Alignment::Center => (gap / 2, gap - gap / 2),
+                };
+                write!(line, "{}{}", " ".repeat(left), cell)?;
+                if !cell.is_empty() {
+                    end = line.len();
+                }
+                line.push_str(&" ".repeat(right));
+            }
+            line.truncate(end);
+            writeln!(f, "{}", line)?;
+
+            if i == 0 && self.header {
+                let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+                writeln!(f, "{}", rule.join("-+-"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_bytes_truncates_and_pads() {
+        let bytes = HexBytes(&[0x01, 0xab, 0xff]);
+        assert_eq!(format!("{:#.1}", bytes), "0x01..");
+        assert_eq!(format!("{:.5}", bytes), "01 ab ff");
+        assert_eq!(format!("[{:-<10}]", bytes), "[01 ab ff--]");
+        assert_eq!(format!("{}", HexBytes(&[])), "");
+    }
+
+    #[test]
+    fn table_aligns_columns_and_fills_ragged_rows() {
+        let rows = vec![
+            vec!["a".to_string(), "bb".to_string(), "c".to_string()],
+            vec!["dddd".to_string()],
+            vec!["e".to_string(), "f".to_string(), "ggggg".to_string()],
+        ];
+        let table = Table::new(rows)
+            .align(0, Alignment::Right)
+            .align(2, Alignment::Center);
+        assert_eq!(
+            table.to_string(),
+            "   a | bb |   c\n\
+             dddd |    |\n   \
+                e | f  | ggggg\n"
+        );
+        assert_eq!(Table::new(Vec::new()).to_string(), "");
+    }
+
+    #[test]
+    fn table_aligns_by_display_width() {
+        let rows = vec![
+            vec!["名字".to_string(), "x".to_string()],
+            vec!["ab".to_string(), "y".to_string()],
+        ];
+        assert_eq!(Table::new(rows).to_string(), "名字 | x\nab   | y\n");
+
+        let rows = vec![
+            vec!["a".to_string(), "b".to_string()],
+            vec!["中".to_string(), "文字".to_string()],
+        ];
+        let table = Table::new(rows)
+            .align(0, Alignment::Right)
+            .align(1, Alignment::Center);
+        assert_eq!(table.to_string(), " a |  b\n中 | 文字\n");
+    }
+
+    #[test]
+    fn table_keeps_trailing_spaces_in_cells() {
+        let rows = vec![
+            vec!["a".to_string(), "x  ".to_string()],
+            vec!["bb".to_string(), "y".to_string()],
+        ];
+        assert_eq!(Table::new(rows).to_string(), "a  | x  \nbb | y\n");
+    }
+
+    struct Padded(&'static str);
+
+    impl fmt::Display for Padded {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            pad_aligned(f, self.0, Alignment::Left)
+        }
+    }
+
+    #[test]
+    fn pad_aligned_counts_display_width() {
+        assert_eq!(format!("[{:>6}]", Padded("中文")), "[  中文]");
+        assert_eq!(format!("[{:*^7}]", Padded("中文")), "[*中文**]");
+        assert_eq!(format!("[{:6}]", Padded("ab")), "[ab    ]");
+        assert_eq!(format!("[{:3}]", Padded("中文")), "[中文]");
+    }
+}
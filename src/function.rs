@@ -1,6 +1,314 @@
-pub fn function() {}
+use std::{collections::HashMap, process};
+
+pub fn function() {
+    // 函数
+    // Rust 的函数使用 fn 声明，参数必须标注类型，返回值类型写在 -> 后面，函数体最后一个表达式就是返回值
+    fn plus_or_minus(x: i32) -> i32 {
+        if x > 5 {
+            return x - 5; // 提前返回需要 return
+        }
+        x + 5
+    }
+    assert_eq!(plus_or_minus(10), 5);
+    assert_eq!(plus_or_minus(1), 6);
+
+    // 函数指针
+    // 函数本身也是值，它的类型是 fn(i64, i64) -> i64 这样的函数指针，可以存放在变量、集合中，也可以作为参数传递。
+    // 不捕获环境变量的闭包同样可以被强制转换为函数指针。
+    let table = dispatch_table();
+    let mut ops: Vec<&&str> = table.keys().collect();
+    ops.sort(); // HashMap 的遍历顺序是不确定的，排序之后再输出
+    for op in ops {
+        println!("7 {} 3 = {}", op, table[*op](7, 3));
+    }
+    assert_eq!(eval(&table, "*", 6, 7), Some(42));
+    assert_eq!(eval(&table, "?", 6, 7), None);
+
+    let double: fn(i64) -> i64 = |x| x * 2;
+    println!("double(21) = {}", double(21));
+
+    // 高阶函数：接收函数作为参数，或者返回一个函数
+    let inc_then_double = compose(|x: i64| x + 1, double);
+    assert_eq!(inc_then_double(4), 10);
+    assert_eq!(apply_n(double, 10, 1), 1024);
+    let add = curry2(|a: i64, b: i64| a + b);
+    let add_ten = add(10);
+    assert_eq!(add_ten(5), 15);
+    println!(
+        "compose: {}, apply_n: {}, curry2: {}",
+        inc_then_double(4),
+        apply_n(double, 10, 1),
+        add_ten(5)
+    );
+
+    // 永不返回的发散函数 !
+    // 当用 ! 作函数返回类型的时候，表示该函数永不返回(见文件末尾的 dead_end)。! 类型的表达式可以被强制转换为任意类型，
+    // 所以 panic!、continue、return、loop {}、process::exit 都可以出现在 match 的分支中，而不会破坏分支类型的一致性：
+    assert_eq!(sum_numbers(&["1", "x", "2", "3"]), 6);
+    assert_eq!(digit('7'), 7);
+
+    // 没有 break 的 loop 的类型也是 !，带 break 值的 loop 的类型则是 break 后面的值
+    let mut n = 1;
+    let first = loop {
+        n *= 3;
+        if n > 100 {
+            break n;
+        }
+    };
+    println!("first power of 3 above 100: {}", first);
+
+    // process::exit 直接结束进程，同样是一个发散函数，注意它不会执行任何 drop
+    // exit_with(1, "bye"); // 这里如果执行，整个程序都会退出
+    // 发散函数也可以当作函数指针使用，返回类型中的 ! 同样会保留下来
+    let _never: fn() -> ! = dead_end;
+}
+
+/// 二元运算的函数指针类型。
+pub type BinOp = fn(i64, i64) -> i64;
+
+// 所有运算都用 checked_* 版本：溢出时返回 None，unwrap_or_else 的闭包返回 !，可以当作 i64 使用
+fn no_overflow(result: Option<i64>) -> i64 {
+    result.unwrap_or_else(|| fail("overflow"))
+}
+
+fn add(a: i64, b: i64) -> i64 {
+    no_overflow(a.checked_add(b))
+}
+
+fn sub(a: i64, b: i64) -> i64 {
+    no_overflow(a.checked_sub(b))
+}
+
+fn mul(a: i64, b: i64) -> i64 {
+    no_overflow(a.checked_mul(b))
+}
+
+// checked_div 在除数为 0 和 i64::MIN / -1 溢出时都返回 None，这里先把除数为 0 单独区分出来
+fn div(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        fail("division by zero");
+    }
+    no_overflow(a.checked_div(b))
+}
+
+/// 运算符到函数指针的分发表，普通函数、闭包和标准库中的方法都可以放进去。
+///
+/// ```
+/// use rust_thinking::function::{dispatch_table, eval};
+///
+/// let table = dispatch_table();
+/// assert_eq!(eval(&table, "-", 7, 3), Some(4));
+/// assert_eq!(eval(&table, "max", 7, 3), Some(7));
+/// assert_eq!(eval(&table, "^", 7, 3), None);
+/// ```
+pub fn dispatch_table() -> HashMap<&'static str, BinOp> {
+    let mut table: HashMap<&'static str, BinOp> = HashMap::new();
+    table.insert("+", add);
+    table.insert("-", sub);
+    table.insert("*", mul);
+    table.insert("/", div);
+    table.insert("%", |a, b| {
+        if b == 0 {
+            fail("division by zero");
+        }
+        no_overflow(a.checked_rem(b))
+    });
+    table.insert("max", i64::max);
+    table.insert("min", i64::min);
+    table
+}
+
+// 运算符不存在时返回 None
+pub fn eval(table: &HashMap<&str, BinOp>, op: &str, a: i64, b: i64) -> Option<i64> {
+    table.get(op).map(|f| f(a, b))
+}
+
+/// 函数组合：先执行 `f`，再把结果交给 `g`。
+///
+/// ```
+/// use rust_thinking::function::compose;
+///
+/// let len_then_double = compose(|s: &str| s.len(), |n| n * 2);
+/// assert_eq!(len_then_double("abc"), 6);
+/// ```
+pub fn compose<A, B, C>(f: impl Fn(A) -> B, g: impl Fn(B) -> C) -> impl Fn(A) -> C {
+    move |x| g(f(x))
+}
+
+/// 把 `f` 连续作用 `n` 次，`n` 为 0 时原样返回 `x`。
+pub fn apply_n<T>(f: impl Fn(T) -> T, n: usize, x: T) -> T {
+    (0..n).fold(x, |acc, _| f(acc))
+}
+
+/// 柯里化：把接收两个参数的函数变成一次接收一个参数的函数链。
+/// 返回位置的 `impl Fn` 不能再嵌套 `impl Fn`，所以内层函数需要装箱。
+///
+/// ```
+/// use rust_thinking::function::curry2;
+///
+/// let pow = curry2(i64::pow);
+/// let two_to = pow(2);
+/// assert_eq!(two_to(10), 1024);
+/// ```
+pub fn curry2<'a, A: Copy + 'a, B, C>(
+    f: impl Fn(A, B) -> C + Copy + 'a,
+) -> impl Fn(A) -> Box<dyn Fn(B) -> C + 'a> {
+    move |a| Box::new(move |b| f(a, b))
+}
+
+// 跳过无法解析的条目：continue 的类型是 !，可以出现在需要 i64 的分支里
+pub fn sum_numbers(items: &[&str]) -> i64 {
+    let mut sum = 0;
+    for item in items {
+        let n: i64 = match item.parse() {
+            Ok(n) => n,
+            Err(_) => continue,
+        };
+        sum += n;
+    }
+    sum
+}
+
+// 不是数字时 panic，panic! 的类型同样是 !
+pub fn digit(c: char) -> u32 {
+    match c.to_digit(10) {
+        Some(d) => d,
+        None => panic!("`{}` is not a digit", c),
+    }
+}
+
+// 以 panic 的方式终止，用在 unwrap_or_else 等需要返回值的位置
+pub fn fail(msg: &str) -> ! {
+    panic!("{}", msg)
+}
+
+// 事件循环：没有 break 的 loop 永远不会结束，只能通过 handle 中的 panic 或者 exit 离开
+pub fn event_loop(mut handle: impl FnMut(u64)) -> ! {
+    let mut tick = 0;
+    loop {
+        handle(tick);
+        tick += 1;
+    }
+}
+
+// 打印信息后以指定的退出码结束进程
+pub fn exit_with(code: i32, msg: &str) -> ! {
+    eprintln!("{}", msg);
+    process::exit(code)
+}
 
 // 当用 ! 作函数返回类型的时候，表示该函数永不返回( diverge function )，特别的，这种语法往往用做会导致程序崩溃的函数：
 fn dead_end() -> ! {
     panic!("你已经到了穷途末路，崩溃吧！");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        env,
+        panic::{self, AssertUnwindSafe},
+        process::Command,
+    };
+
+    #[test]
+    fn dispatch_table_covers_operators() {
+        let table = dispatch_table();
+        let cases = [
+            ("+", 7),
+            ("-", 3),
+            ("*", 10),
+            ("/", 2),
+            ("%", 1),
+            ("max", 5),
+            ("min", 2),
+        ];
+        for (op, expected) in cases {
+            assert_eq!(eval(&table, op, 5, 2), Some(expected), "op {}", op);
+        }
+        assert_eq!(eval(&table, "pow", 5, 2), None);
+        assert_eq!(table.len(), cases.len());
+    }
+
+    fn panic_message(table: &HashMap<&str, BinOp>, op: &str, a: i64, b: i64) -> String {
+        let payload = panic::catch_unwind(|| eval(table, op, a, b)).unwrap_err();
+        payload.downcast_ref::<String>().unwrap().clone()
+    }
+
+    #[test]
+    fn division_by_zero_diverges() {
+        let table = dispatch_table();
+        assert_eq!(panic_message(&table, "/", 1, 0), "division by zero");
+        assert_eq!(panic_message(&table, "%", 1, 0), "division by zero");
+    }
+
+    #[test]
+    fn overflow_is_reported_separately() {
+        let table = dispatch_table();
+        assert_eq!(panic_message(&table, "+", i64::MAX, 1), "overflow");
+        assert_eq!(panic_message(&table, "-", i64::MIN, 1), "overflow");
+        assert_eq!(panic_message(&table, "*", i64::MAX, 2), "overflow");
+        assert_eq!(panic_message(&table, "/", i64::MIN, -1), "overflow");
+        assert_eq!(panic_message(&table, "%", i64::MIN, -1), "overflow");
+        assert_eq!(eval(&table, "+", i64::MAX - 1, 1), Some(i64::MAX));
+    }
+
+    #[test]
+    fn higher_order_helpers() {
+        let shout = compose(|s: &str| s.to_uppercase(), |s| s + "!");
+        assert_eq!(shout("hi"), "HI!");
+
+        assert_eq!(apply_n(|x: i32| x + 3, 0, 1), 1);
+        assert_eq!(apply_n(|s: String| s + "a", 3, String::new()), "aaa");
+
+        let sub = curry2(|a: i64, b: i64| a - b);
+        let from_ten = sub(10);
+        assert_eq!(from_ten(3), 7);
+        assert_eq!(from_ten(-3), 13);
+        assert_eq!(sub(0)(1), -1);
+    }
+
+    #[test]
+    fn never_type_in_match_arms() {
+        assert_eq!(sum_numbers(&["4", "", "-1", "1.5"]), 3);
+        assert_eq!(digit('0'), 0);
+        assert!(panic::catch_unwind(|| digit('a')).is_err());
+        assert!(panic::catch_unwind(|| dead_end()).is_err());
+    }
+
+    #[test]
+    fn loop_without_break_only_leaves_by_diverging() {
+        let mut seen = Vec::new();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            event_loop(|tick| {
+                if tick == 3 {
+                    fail("stop");
+                }
+                seen.push(tick);
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(seen, vec![0, 1, 2]);
+    }
+
+    const EXIT_CHILD: &str = "RUST_THINKING_EXIT_CHILD";
+
+    // 只有在被下面的测试以子进程方式启动时才会真正调用 exit_with
+    #[test]
+    fn exit_child() {
+        if env::var_os(EXIT_CHILD).is_some() {
+            exit_with(3, "exiting from child");
+        }
+    }
+
+    #[test]
+    fn process_exit_ends_the_process() {
+        let output = Command::new(env::current_exe().unwrap())
+            .args(["--exact", "function::tests::exit_child", "--nocapture"])
+            .env(EXIT_CHILD, "1")
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("exiting from child"));
+    }
+}
@@ -1,3 +1,6 @@
+use crate::geometry;
+
+// 这里为了演示泛型结构体的定义语法保留了一个局部的 Point<T>，可复用的实现见 geometry::Point
 #[derive(Debug)]
 struct Point<T> {
     x: T,
@@ -65,6 +68,10 @@ pub fn generics() {
             (self.x.powi(2) + self.y.powi(2)).sqrt()
         }
     }
+    // geometry 模块中的 Vector2<T>(别名 Point<T>)把需要的数值运算抽象成了 Num 特征，length 对整数和浮点数都可用
+    let p = geometry::Point::new(3, 4);
+    let q = geometry::Point::new(1.5, 2.0);
+    println!("|{}| = {}, |{}| = {}", p, p.length(), q, q.length());
 
     // const 泛型（Rust 1.51 版本引入的重要特性）
     // 在之前的泛型中，可以抽象为一句话：针对类型实现的泛型，所有的泛型都是为了抽象不同的类型，那有没有针对值的泛型？
@@ -0,0 +1,454 @@
+use std::{
+    fmt::{self, Alignment},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use crate::format_output::pad_aligned;
+
+// 二维向量
+// pattern_matching_3/4、format_output 和 deep_trait 的 OutlinePrint 示例直接使用这里的 Point；deep_trait 的 Add 示例和 generics 为了演示语法保留了局部的 Point，
+// generics 中的 distance_from_origin 也只能用于 Point<f32>。
+// 这里用一个数值特征 Num 把整数和浮点数统一起来，只写一份 Vector2<T>：
+// 1. 加减、取负、数乘以及对应的 *Assign 运算对所有 Num 都可用(取负要求 T: Neg，即有符号类型)
+// 2. 点积、叉积、长度对所有 Num 都可用，长度统一以 f64 返回
+// 3. 单位化和线性插值只对浮点数有意义，要求 T: Float
+// 4. Display 处理精度、符号、# 和宽度等格式化参数，format_output 章节用它演示 Formatter
+
+/// 向量分量需要支持的数值运算，整数和浮点数都实现了它。
+pub trait Num:
+    Copy
+    + PartialEq
+    + PartialOrd
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn to_f64(self) -> f64;
+}
+
+/// 浮点数额外提供的运算。
+pub trait Float: Num + Neg<Output = Self> {
+    fn sqrt(self) -> Self;
+}
+
+macro_rules! num {
+    ($zero:literal, $one:literal: $($t:ty),*) => {
+        $(
+            impl Num for $t {
+                const ZERO: $t = $zero;
+                const ONE: $t = $one;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+
+            // 标量在左侧的乘法 k * v，孤儿规则不允许为泛型 T 实现，只能逐个类型实现
+            impl Mul<Vector2<$t>> for $t {
+                type Output = Vector2<$t>;
+
+                fn mul(self, v: Vector2<$t>) -> Vector2<$t> {
+                    v * self
+                }
+            }
+        )*
+    };
+}
+
+num!(0, 1: i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+num!(0.0, 1.0: f32, f64);
+
+macro_rules! float {
+    ($($t:ty),*) => {
+        $(
+            impl Float for $t {
+                fn sqrt(self) -> $t {
+                    <$t>::sqrt(self)
+                }
+            }
+        )*
+    };
+}
+
+float!(f32, f64);
+
+/// 二维向量，分量可以是任意实现了 [`Num`] 的类型。
+///
+/// ```
+/// use rust_thinking::geometry::{Point, Vector2};
+///
+/// let a = Point::new(3, 4);
+/// let b = Point::new(1, -2);
+/// assert_eq!(a + b, Point::new(4, 2));
+/// assert_eq!(a.dot(b), -5);
+/// assert_eq!(a.length(), 5.0);
+///
+/// let v = Vector2::new(3.0, 4.0).normalize().unwrap();
+/// assert_eq!(v, Vector2::new(0.6, 0.8));
+/// assert_eq!(Vector2::new(0.0, 0.0).lerp(Vector2::new(2.0, 4.0), 0.25), Vector2::new(0.5, 1.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Vector2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// 点和向量使用同一个类型，点之间的差就是向量。
+pub type Point<T> = Vector2<T>;
+
+impl<T: Num> Vector2<T> {
+    pub fn new(x: T, y: T) -> Vector2<T> {
+        Vector2 { x, y }
+    }
+
+    pub fn zero() -> Vector2<T> {
+        Vector2::new(T::ZERO, T::ZERO)
+    }
+
+    pub fn dot(self, other: Vector2<T>) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    // 二维叉积是一个标量：以 self 为起点逆时针转到 other 时为正，顺时针为负，共线为 0
+    pub fn cross(self, other: Vector2<T>) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn length_squared(self) -> T {
+        self.dot(self)
+    }
+
+    pub fn length(self) -> f64 {
+        let (x, y) = (self.x.to_f64(), self.y.to_f64());
+        x.hypot(y)
+    }
+
+    pub fn distance(self, other: Vector2<T>) -> f64 {
+        let (dx, dy) = (
+            self.x.to_f64() - other.x.to_f64(),
+            self.y.to_f64() - other.y.to_f64(),
+        );
+        dx.hypot(dy)
+    }
+}
+
+impl<T: Float> Vector2<T> {
+    // 零向量没有方向，返回 None
+    pub fn normalize(self) -> Option<Vector2<T>> {
+        let length = self.length_squared().sqrt();
+        if length == T::ZERO {
+            None
+        } else {
+            Some(self / length)
+        }
+    }
+
+    // t = 0 时为 self，t = 1 时为 other，t 超出 [0, 1] 时沿直线外推
+    pub fn lerp(self, other: Vector2<T>, t: T) -> Vector2<T> {
+        self + (other - self) * t
+    }
+}
+
+impl<T: Num> Add for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn add(self, other: Vector2<T>) -> Vector2<T> {
+        Vector2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Num> Sub for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn sub(self, other: Vector2<T>) -> Vector2<T> {
+        Vector2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Num + Neg<Output = T>> Neg for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn neg(self) -> Vector2<T> {
+        Vector2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Num> Mul<T> for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn mul(self, k: T) -> Vector2<T> {
+        Vector2::new(self.x * k, self.y * k)
+    }
+}
+
+impl<T: Num> Div<T> for Vector2<T> {
+    type Output = Vector2<T>;
+
+    fn div(self, k: T) -> Vector2<T> {
+        Vector2::new(self.x / k, self.y / k)
+    }
+}
+
+impl<T: Num> AddAssign for Vector2<T> {
+    fn add_assign(&mut self, other: Vector2<T>) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: Num> SubAssign for Vector2<T> {
+    fn sub_assign(&mut self, other: Vector2<T>) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T: Num> MulAssign<T> for Vector2<T> {
+    fn mul_assign(&mut self, k: T) {
+        self.x *= k;
+        self.y *= k;
+    }
+}
+
+impl<T: Num> DivAssign<T> for Vector2<T> {
+    fn div_assign(&mut self, k: T) {
+        self.x /= k;
+        self.y /= k;
+    }
+}
+
+impl<T> From<(T, T)> for Vector2<T> {
+    fn from((x, y): (T, T)) -> Vector2<T> {
+        Vector2 { x, y }
+    }
+}
+
+impl<T> From<Vector2<T>> for (T, T) {
+    fn from(v: Vector2<T>) -> (T, T) {
+        (v.x, v.y)
+    }
+}
+
+// 按照 f 中的精度和符号参数格式化单个分量
+fn component<T: fmt::Display>(f: &fmt::Formatter, v: &T) -> String {
+    match (f.sign_plus(), f.precision()) {
+        (true, Some(p)) => format!("{:+.*}", p, v),
+        (true, None) => format!("{:+}", v),
+        (false, Some(p)) => format!("{:.*}", p, v),
+        (false, None) => format!("{}", v),
+    }
+}
+
+/// Display 会处理所有常见的格式化参数(format_output 章节)：
+///
+/// - 精度和 `+` 号作用在每个分量上
+/// - `#` 切换为带字段名的写法
+/// - 宽度、填充和对齐作用在整体上
+///
+/// ```
+/// use rust_thinking::geometry::Point;
+///
+/// let p = Point::new(1.0, -2.5);
+/// assert_eq!(format!("{}", p), "(1, -2.5)");
+/// assert_eq!(format!("{:+.1}", p), "(+1.0, -2.5)");
+/// assert_eq!(format!("{:#}", p), "x = 1, y = -2.5");
+/// assert_eq!(format!("{:_>12}", p), "___(1, -2.5)");
+/// assert_eq!(format!("{:+}", Point::new(3, 0)), "(+3, +0)");
+/// ```
+impl<T: fmt::Display> fmt::Display for Vector2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (x, y) = (component(f, &self.x), component(f, &self.y));
+        let s = if f.alternate() {
+            format!("x = {}, y = {}", x, y)
+        } else {
+            format!("({}, {})", x, y)
+        };
+        pad_aligned(f, &s, Alignment::Left)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::Rng;
+
+    const CASES: usize = 500;
+
+    // 分量限制在 [-1000, 1000) 内，乘法和点积不会溢出
+    fn int_vector(rng: &mut Rng) -> Vector2<i64> {
+        let mut component = || rng.below(2000) as i64 - 1000;
+        Vector2::new(component(), component())
+    }
+
+    fn float_vector(rng: &mut Rng) -> Vector2<f64> {
+        let mut component = || rng.next_f64() * 200.0 - 100.0;
+        Vector2::new(component(), component())
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * (1.0 + a.abs().max(b.abs()))
+    }
+
+    fn close_vec(a: Vector2<f64>, b: Vector2<f64>) -> bool {
+        close(a.x, b.x) && close(a.y, b.y)
+    }
+
+    #[test]
+    fn integer_vectors_form_a_module() {
+        let mut rng = Rng::new(15);
+        for _ in 0..CASES {
+            let (a, b, c) = (
+                int_vector(&mut rng),
+                int_vector(&mut rng),
+                int_vector(&mut rng),
+            );
+            let (k, m) = (rng.below(21) as i64 - 10, rng.below(21) as i64 - 10);
+            let zero = Vector2::zero();
+
+            assert_eq!(a + b, b + a);
+            assert_eq!((a + b) + c, a + (b + c));
+            assert_eq!(a + zero, a);
+            assert_eq!(a + -a, zero);
+            assert_eq!(a - b, a + -b);
+            assert_eq!(-(-a), a);
+
+            assert_eq!((a + b) * k, a * k + b * k);
+            assert_eq!(a * (k + m), a * k + a * m);
+            assert_eq!(a * k * m, a * (k * m));
+            assert_eq!(a * 1, a);
+            assert_eq!(k * a, a * k);
+
+            assert_eq!(a.dot(b), b.dot(a));
+            assert_eq!(a.dot(b + c), a.dot(b) + a.dot(c));
+            assert_eq!((a * k).dot(b), k * a.dot(b));
+            assert!(a.length_squared() >= 0);
+
+            assert_eq!(a.cross(b), -b.cross(a));
+            assert_eq!(a.cross(a), 0);
+            assert_eq!(a.cross(b + c), a.cross(b) + a.cross(c));
+            // |a|²|b|² = (a·b)² + (a×b)²
+            assert_eq!(
+                a.length_squared() * b.length_squared(),
+                a.dot(b).pow(2) + a.cross(b).pow(2)
+            );
+        }
+    }
+
+    #[test]
+    fn assign_ops_agree_with_binary_ops() {
+        let mut rng = Rng::new(16);
+        for _ in 0..CASES {
+            let (a, b) = (int_vector(&mut rng), int_vector(&mut rng));
+            let k = rng.below(9) as i64 + 1;
+
+            let mut v = a;
+            v += b;
+            assert_eq!(v, a + b);
+            v -= b;
+            assert_eq!(v, a);
+            v *= k;
+            assert_eq!(v, a * k);
+            v /= k;
+            assert_eq!(v, a);
+
+            let mut f = float_vector(&mut rng);
+            let g = float_vector(&mut rng);
+            let expected = (f + g) * 0.5;
+            f += g;
+            f *= 0.5;
+            assert_eq!(f, expected);
+        }
+    }
+
+    #[test]
+    fn float_vectors_satisfy_the_laws_approximately() {
+        let mut rng = Rng::new(17);
+        for _ in 0..CASES {
+            let (a, b, c) = (
+                float_vector(&mut rng),
+                float_vector(&mut rng),
+                float_vector(&mut rng),
+            );
+            let t = rng.next_f64();
+
+            assert_eq!(a + b, b + a);
+            assert!(close_vec((a + b) + c, a + (b + c)));
+            assert!(close(a.dot(b + c), a.dot(b) + a.dot(c)));
+            assert!(close(a.cross(b), -b.cross(a)));
+
+            // 三角不等式和 Cauchy-Schwarz 不等式
+            assert!((a + b).length() <= a.length() + b.length() + 1e-9);
+            assert!(a.dot(b).abs() <= a.length() * b.length() + 1e-9);
+            assert!(close(a.distance(b), (a - b).length()));
+
+            let n = a.normalize().unwrap();
+            assert!(close(n.length(), 1.0));
+            assert!(close(n.cross(a), 0.0));
+            assert!(n.dot(a) > 0.0);
+
+            assert_eq!(a.lerp(b, 0.0), a);
+            assert!(close_vec(a.lerp(b, 1.0), b));
+            assert!(close_vec(a.lerp(b, 0.5), (a + b) / 2.0));
+            let p = a.lerp(b, t);
+            assert!(close(a.distance(p) + p.distance(b), a.distance(b)));
+        }
+    }
+
+    #[test]
+    fn works_for_unsigned_and_f32() {
+        let a: Point<u8> = Point::new(3, 4);
+        assert_eq!(a * 2, Point::new(6, 8));
+        assert_eq!(a.length(), 5.0);
+        assert_eq!(a.distance(Point::new(0, 0)), 5.0);
+        assert_eq!(Point::new(6u32, 8) / 2, Point::new(3, 4));
+
+        let v = Vector2::new(0.0f32, -2.0);
+        assert_eq!(v.normalize(), Some(Vector2::new(0.0, -1.0)));
+        assert_eq!(Vector2::<f32>::zero().normalize(), None);
+        assert_eq!(-v, Vector2::new(-0.0, 2.0));
+    }
+
+    #[test]
+    fn conversions_and_display() {
+        let p: Point<i32> = (1, -2).into();
+        assert_eq!(p, Point { x: 1, y: -2 });
+        assert_eq!(<(i32, i32)>::from(p), (1, -2));
+        assert_eq!(p.to_string(), "(1, -2)");
+        assert_eq!(format!("{:.1}", Vector2::new(0.26, 2.0)), "(0.3, 2.0)");
+    }
+
+    #[test]
+    fn display_honors_formatter_flags() {
+        let p = Point::new(1.5, -2.0);
+        assert_eq!(format!("{}", p), "(1.5, -2)");
+        assert_eq!(format!("{:.2}", p), "(1.50, -2.00)");
+        assert_eq!(format!("{:+}", p), "(+1.5, -2)");
+        assert_eq!(format!("{:#.1}", p), "x = 1.5, y = -2.0");
+        assert_eq!(format!("[{:12}]", p), "[(1.5, -2)   ]");
+        assert_eq!(format!("[{:>12}]", p), "[   (1.5, -2)]");
+        assert_eq!(format!("[{:*^13}]", p), "[**(1.5, -2)**]");
+        // 宽度小于内容长度时不截断
+        assert_eq!(format!("{:3}", p), "(1.5, -2)");
+        assert_eq!(format!("[{:>8}]", Point::new(3, -4)), "[ (3, -4)]");
+    }
+
+    #[test]
+    fn debug_forwards_to_fields() {
+        let p = Point::new(1.0, 2.0);
+        assert_eq!(format!("{:?}", p), "Vector2 { x: 1.0, y: 2.0 }");
+        assert_eq!(format!("{:.2?}", p), "Vector2 { x: 1.00, y: 2.00 }");
+        assert_eq!(
+            format!("{:#?}", p),
+            "Vector2 {\n    x: 1.0,\n    y: 2.0,\n}"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use std::cell::Cell;
+
+// 原地切片算法
+// refcell::retain_even 用 Cell::from_mut(..).as_slice_of_cells() 把 &mut [T] 变成 &[Cell<T>]，
+// 于是可以一边用迭代器遍历切片，一边通过 set 改写前面的元素，不需要下标循环，也不需要额外分配内存。
+// 这里用同样的办法实现一组算法，它们都只接受 &mut [T]：
+// 1. 切片不能改变长度，会"删除"元素的算法返回保留下来的元素个数 n，结果在 slice[..n] 中，slice[n..] 的内容不作保证，调用者可以用 Vec::truncate(n) 截断
+// 2. 读取元素用的是 Cell::get，移动元素用的是 Cell::set 和 Cell::swap，所以和 retain_even 一样要求 T: Copy
+// 3. 除了 compact_stable 的递归栈(深度为 log n)之外，没有任何额外的内存分配
+
+fn cells<T>(slice: &mut [T]) -> &[Cell<T>] {
+    Cell::from_mut(slice).as_slice_of_cells()
+}
+
+/// 保留满足 `keep` 的元素，保持它们的相对顺序，返回保留的个数。
+///
+/// ```
+/// use rust_thinking::in_place::retain_by;
+///
+/// let mut nums = vec![1, 2, 3, 4, 5, 6];
+/// let n = retain_by(&mut nums, |x| x % 2 == 0);
+/// nums.truncate(n);
+/// assert_eq!(nums, [2, 4, 6]);
+/// ```
+pub fn retain_by<T: Copy>(slice: &mut [T], mut keep: impl FnMut(&T) -> bool) -> usize {
+    let slice = cells(slice);
+    let mut kept = 0;
+    for cell in slice.iter().filter(|cell| keep(&cell.get())) {
+        slice[kept].set(cell.get());
+        kept += 1;
+    }
+    kept
+}
+
+/// 去掉连续的重复元素，返回保留的个数。
+///
+/// 和 `Vec::dedup_by` 一样，`same(a, b)` 中 `a` 是当前元素，`b` 是上一个保留下来的元素。
+///
+/// ```
+/// use rust_thinking::in_place::dedup_by;
+///
+/// let mut words = ["a", "A", "b", "B", "b", "a"];
+/// let n = dedup_by(&mut words, |a, b| a.eq_ignore_ascii_case(b));
+/// assert_eq!(words[..n], ["a", "b", "a"]);
+/// ```
+pub fn dedup_by<T: Copy>(slice: &mut [T], mut same: impl FnMut(&T, &T) -> bool) -> usize {
+    let slice = cells(slice);
+    let mut kept = 0;
+    for cell in slice.iter() {
+        if kept == 0 || !same(&cell.get(), &slice[kept - 1].get()) {
+            slice[kept].set(cell.get());
+            kept += 1;
+        }
+    }
+    kept
+}
+
+/// 把满足 `pred` 的元素交换到前面，返回它们的个数；两部分内部的顺序都不保证。
+///
+/// ```
+/// use rust_thinking::in_place::partition_in_place;
+///
+/// let mut nums = [1, 2, 3, 4, 5, 6];
+/// let n = partition_in_place(&mut nums, |x| x % 3 == 0);
+/// assert_eq!(n, 2);
+/// assert!(nums[..n].iter().all(|x| x % 3 == 0));
+/// assert!(nums[n..].iter().all(|x| x % 3 != 0));
+/// ```
+pub fn partition_in_place<T: Copy>(slice: &mut [T], mut pred: impl FnMut(&T) -> bool) -> usize {
+    let slice = cells(slice);
+    // 左右两个游标相向移动，左边遇到不满足的、右边遇到满足的就交换
+    let (mut front, mut back) = (0, slice.len());
+    loop {
+        while front < back && pred(&slice[front].get()) {
+            front += 1;
+        }
+        while front < back && !pred(&slice[back - 1].get()) {
+            back -= 1;
+        }
+        if front == back {
+            return front;
+        }
+        slice[front].swap(&slice[back - 1]);
+        front += 1;
+        back -= 1;
+    }
+}
+
+// 三次翻转实现的左旋：先分别翻转 [..mid] 和 [mid..]，再翻转整体
+fn rotate_left<T>(slice: &[Cell<T>], mid: usize) {
+    reverse(&slice[..mid]);
+    reverse(&slice[mid..]);
+    reverse(slice);
+}
+
+fn reverse<T>(slice: &[Cell<T>]) {
+    let half = slice.len() / 2;
+    for (front, back) in slice[..half].iter().zip(slice[half..].iter().rev()) {
+        front.swap(back);
+    }
+}
+
+/// 把切片循环左移，使第一个满足 `pred` 的元素成为开头，返回移动的位数；没有满足的元素时切片保持不变。
+///
+/// ```
+/// use rust_thinking::in_place::rotate_by_predicate;
+///
+/// let mut ring = [3, 4, 0, 1, 2];
+/// assert_eq!(rotate_by_predicate(&mut ring, |&x| x == 0), Some(2));
+/// assert_eq!(ring, [0, 1, 2, 3, 4]);
+/// assert_eq!(rotate_by_predicate(&mut ring, |&x| x > 9), None);
+/// ```
+pub fn rotate_by_predicate<T: Copy>(
+    slice: &mut [T],
+    mut pred: impl FnMut(&T) -> bool,
+) -> Option<usize> {
+    let slice = cells(slice);
+    let mid = slice.iter().position(|cell| pred(&cell.get()))?;
+    rotate_left(slice, mid);
+    Some(mid)
+}
+
+/// 稳定的压缩：满足 `keep` 的元素移到前面，返回它们的个数。
+///
+/// 和 [`retain_by`] 不同，不满足的元素也完整保留在 `slice[n..]` 中，两部分都保持原来的相对顺序。
+/// 采用分治加旋转的做法，时间复杂度 O(n log n)，`keep` 对每个元素只调用一次。
+///
+/// ```
+/// use rust_thinking::in_place::compact_stable;
+///
+/// let mut nums = [1, 2, 3, 4, 5, 6];
+/// assert_eq!(compact_stable(&mut nums, |x| x % 2 == 0), 3);
+/// assert_eq!(nums, [2, 4, 6, 1, 3, 5]);
+/// ```
+pub fn compact_stable<T: Copy>(slice: &mut [T], mut keep: impl FnMut(&T) -> bool) -> usize {
+    fn go<T: Copy>(slice: &[Cell<T>], keep: &mut impl FnMut(&T) -> bool) -> usize {
+        match slice.len() {
+            0 => 0,
+            1 => keep(&slice[0].get()) as usize,
+            len => {
+                // 两半各自压缩之后是 [保留 | 丢弃 | 保留 | 丢弃]，把中间两段旋转一下就合并好了
+                let mid = len / 2;
+                let left = go(&slice[..mid], keep);
+                let right = go(&slice[mid..], keep);
+                rotate_left(&slice[left..mid + right], mid - left);
+                left + right
+            }
+        }
+    }
+    go(cells(slice), &mut keep)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::Rng;
+
+    fn random_vec(rng: &mut Rng) -> Vec<u8> {
+        let len = rng.below(40) as usize;
+        (0..len).map(|_| rng.below(6) as u8).collect()
+    }
+
+    #[test]
+    fn retain_by_matches_vec_retain() {
+        let mut rng = Rng::new(23);
+        for _ in 0..500 {
+            let mut expected = random_vec(&mut rng);
+            let mut actual = expected.clone();
+            let threshold = rng.below(6) as u8;
+            expected.retain(|&x| x < threshold);
+            let n = retain_by(&mut actual, |&x| x < threshold);
+            actual.truncate(n);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn dedup_by_matches_vec_dedup() {
+        let mut rng = Rng::new(23);
+        for _ in 0..500 {
+            let mut expected = random_vec(&mut rng);
+            let mut actual = expected.clone();
+            expected.dedup();
+            let n = dedup_by(&mut actual, |a, b| a == b);
+            actual.truncate(n);
+            assert_eq!(actual, expected);
+
+            // 非对称的比较也要和 std 一致：当前元素不大于上一个保留的元素时去掉
+            let mut expected = random_vec(&mut rng);
+            let mut actual = expected.clone();
+            expected.dedup_by(|a, b| a <= b);
+            let n = dedup_by(&mut actual, |a, b| a <= b);
+            actual.truncate(n);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn partition_keeps_every_element() {
+        let mut rng = Rng::new(23);
+        for _ in 0..500 {
+            let original = random_vec(&mut rng);
+            let mut actual = original.clone();
+            let n = partition_in_place(&mut actual, |&x| x % 2 == 0);
+            assert_eq!(n, original.iter().filter(|&&x| x % 2 == 0).count());
+            assert!(actual[..n].iter().all(|&x| x % 2 == 0));
+            assert!(actual[n..].iter().all(|&x| x % 2 == 1));
+            let (mut sorted, mut expected) = (actual, original);
+            sorted.sort();
+            expected.sort();
+            assert_eq!(sorted, expected);
+        }
+    }
+
+    #[test]
+    fn rotate_matches_rotate_left() {
+        let mut rng = Rng::new(23);
+        for _ in 0..500 {
+            let mut expected = random_vec(&mut rng);
+            let mut actual = expected.clone();
+            let target = rng.below(6) as u8;
+            let mid = expected.iter().position(|&x| x == target);
+            if let Some(mid) = mid {
+                expected.rotate_left(mid);
+            }
+            assert_eq!(rotate_by_predicate(&mut actual, |&x| x == target), mid);
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn compact_stable_matches_two_filters() {
+        let mut rng = Rng::new(23);
+        for _ in 0..500 {
+            // 用下标区分相等的元素，检查两部分的相对顺序
+            let original: Vec<(u8, usize)> = random_vec(&mut rng)
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| (x, i))
+                .collect();
+            let mut expected: Vec<(u8, usize)> =
+                original.iter().copied().filter(|p| p.0 < 3).collect();
+            let n = expected.len();
+            expected.extend(original.iter().copied().filter(|p| p.0 >= 3));
+
+            let mut actual = original.clone();
+            let mut calls = 0;
+            let kept = compact_stable(&mut actual, |p| {
+                calls += 1;
+                p.0 < 3
+            });
+            assert_eq!(kept, n);
+            assert_eq!(actual, expected);
+            assert_eq!(calls, original.len());
+        }
+    }
+}
@@ -0,0 +1,553 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    iter::{Fuse, Peekable},
+    rc::Rc,
+};
+
+// 迭代器扩展特征
+// learn_iterator 章节讲过：只要实现了 next，就能使用 Iterator 上的所有适配器。反过来，我们也可以给所有迭代器添加新的适配器：
+// 定义一个以 Iterator 为 supertrait 的扩展特征，再为所有 I: Iterator 实现它，引入 IterExt 之后任何迭代器都可以调用这些方法。
+// 每个适配器都是一个持有内部迭代器的结构体，和标准库的 Map、Filter 一样是惰性的，只有被消费时才会调用内部迭代器的 next。
+
+/// 为所有迭代器提供的额外适配器。
+///
+/// ```
+/// use rust_thinking::iter_ext::IterExt;
+///
+/// let chunks: Vec<Vec<u32>> = (1..=5).chunks(2).collect();
+/// assert_eq!(chunks, [vec![1, 2], vec![3, 4], vec![5]]);
+///
+/// let groups: Vec<(bool, Vec<u32>)> = [1, 3, 4, 6, 7].into_iter().group_by(|n| n % 2 == 0).collect();
+/// assert_eq!(groups, [(false, vec![1, 3]), (true, vec![4, 6]), (false, vec![7])]);
+/// ```
+pub trait IterExt: Iterator + Sized {
+    /// 每次产出 `n` 个元素组成的 `Vec`，最后一组可能不足 `n` 个。
+    fn chunks(self, n: usize) -> Chunks<Self> {
+        assert!(n > 0, "chunk size must be positive");
+        Chunks {
+            iter: self.fuse(),
+            n,
+        }
+    }
+
+    /// 长度为 `n` 的滑动窗口，元素个数少于 `n` 时不产出任何窗口。
+    fn windows(self, n: usize) -> Windows<Self>
+    where
+        Self::Item: Clone,
+    {
+        assert!(n > 0, "window size must be positive");
+        Windows {
+            iter: self.fuse(),
+            window: VecDeque::with_capacity(n),
+            n,
+        }
+    }
+
+    /// 轮流从两个迭代器中取元素，其中一个耗尽后继续产出另一个剩下的元素。
+    fn interleave<J>(self, other: J) -> Interleave<Self, J::IntoIter>
+    where
+        J: IntoIterator<Item = Self::Item>,
+    {
+        Interleave {
+            a: self.fuse(),
+            b: other.into_iter().fuse(),
+            next_b: false,
+        }
+    }
+
+    /// 去掉键相同的相邻元素，只保留每一段中的第一个，和 `Vec::dedup_by_key` 一致。
+    fn dedup_by_key<K, F>(self, key: F) -> DedupByKey<Self, F, K>
+    where
+        K: PartialEq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        DedupByKey {
+            iter: self,
+            key,
+            last: None,
+        }
+    }
+
+    /// 在相邻的两个元素之间插入 `separator`。
+    ///
+    /// 标准库中有一个同名的不稳定方法，用方法语法调用会触发 `unstable_name_collisions` 警告，
+    /// 可以写成 `IterExt::intersperse(iter, separator)`。
+    fn intersperse(self, separator: Self::Item) -> Intersperse<Self>
+    where
+        Self::Item: Clone,
+    {
+        Intersperse {
+            iter: self.peekable(),
+            separator,
+            needs_separator: false,
+        }
+    }
+
+    /// 把键相同的相邻元素分为一组，产出 `(键, 组内元素)`。
+    ///
+    /// `key` 对每个元素可能调用不止一次，应当是纯函数。
+    fn group_by<K, F>(self, key: F) -> GroupBy<Self, F>
+    where
+        K: PartialEq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        GroupBy {
+            iter: self.peekable(),
+            key,
+        }
+    }
+
+    /// 把一个迭代器复制成两个，各自独立前进；一方领先时，另一方还没取走的元素缓存在队列里。
+    fn tee(self) -> (Tee<Self>, Tee<Self>)
+    where
+        Self::Item: Clone,
+    {
+        let shared = Rc::new(RefCell::new(TeeShared {
+            iter: self,
+            pending: [VecDeque::new(), VecDeque::new()],
+        }));
+        (
+            Tee {
+                shared: Rc::clone(&shared),
+                side: 0,
+            },
+            Tee { shared, side: 1 },
+        )
+    }
+
+    /// 步长由上一个产出的元素决定的 `step_by`：产出 `x` 之后，下一个产出的是它后面第 `step(&x)` 个元素。
+    fn step_by_fn<F>(self, step: F) -> StepByFn<Self, F>
+    where
+        F: FnMut(&Self::Item) -> usize,
+    {
+        StepByFn {
+            iter: self,
+            step,
+            skip: 0,
+        }
+    }
+}
+
+impl<I: Iterator> IterExt for I {}
+
+pub struct Chunks<I: Iterator> {
+    iter: Fuse<I>,
+    n: usize,
+}
+
+impl<I: Iterator> Iterator for Chunks<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        let chunk: Vec<I::Item> = self.iter.by_ref().take(self.n).collect();
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lo, hi) = self.iter.size_hint();
+        (lo.div_ceil(self.n), hi.map(|hi| hi.div_ceil(self.n)))
+    }
+}
+
+pub struct Windows<I: Iterator> {
+    iter: Fuse<I>,
+    window: VecDeque<I::Item>,
+    n: usize,
+}
+
+impl<I: Iterator> Iterator for Windows<I>
+where
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        // 上一个窗口已经产出，先把最旧的元素移出去
+        if self.window.len() == self.n {
+            self.window.pop_front();
+        }
+        while self.window.len() < self.n {
+            self.window.push_back(self.iter.next()?);
+        }
+        Some(self.window.iter().cloned().collect())
+    }
+}
+
+pub struct Interleave<I, J> {
+    a: Fuse<I>,
+    b: Fuse<J>,
+    next_b: bool,
+}
+
+impl<I, J> Iterator for Interleave<I, J>
+where
+    I: Iterator,
+    J: Iterator<Item = I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.next_b = !self.next_b;
+        if self.next_b {
+            self.a.next().or_else(|| self.b.next())
+        } else {
+            self.b.next().or_else(|| self.a.next())
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_lo, a_hi) = self.a.size_hint();
+        let (b_lo, b_hi) = self.b.size_hint();
+        let hi = match (a_hi, b_hi) {
+            (Some(a), Some(b)) => a.checked_add(b),
+            _ => None,
+        };
+        (a_lo.saturating_add(b_lo), hi)
+    }
+}
+
+pub struct DedupByKey<I, F, K> {
+    iter: I,
+    key: F,
+    last: Option<K>,
+}
+
+impl<I, F, K> Iterator for DedupByKey<I, F, K>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        loop {
+            let item = self.iter.next()?;
+            let key = (self.key)(&item);
+            if self.last.as_ref() != Some(&key) {
+                self.last = Some(key);
+                return Some(item);
+            }
+        }
+    }
+}
+
+pub struct Intersperse<I: Iterator> {
+    iter: Peekable<I>,
+    separator: I::Item,
+    needs_separator: bool,
+}
+
+impl<I: Iterator> Iterator for Intersperse<I>
+where
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        // 只有后面还有元素时才插入分隔符，结尾不会多出一个
+        if self.needs_separator && self.iter.peek().is_some() {
+            self.needs_separator = false;
+            return Some(self.separator.clone());
+        }
+        let item = self.iter.next()?;
+        self.needs_separator = true;
+        Some(item)
+    }
+}
+
+pub struct GroupBy<I: Iterator, F> {
+    iter: Peekable<I>,
+    key: F,
+}
+
+impl<I, F, K> Iterator for GroupBy<I, F>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = (K, Vec<I::Item>);
+
+    fn next(&mut self) -> Option<(K, Vec<I::Item>)> {
+        let first = self.iter.next()?;
+        let key = (self.key)(&first);
+        let mut group = vec![first];
+        let f = &mut self.key;
+        while let Some(item) = self.iter.next_if(|item| f(item) == key) {
+            group.push(item);
+        }
+        Some((key, group))
+    }
+}
+
+struct TeeShared<I: Iterator> {
+    iter: I,
+    // 两侧各自还没有取走的元素
+    pending: [VecDeque<I::Item>; 2],
+}
+
+pub struct Tee<I: Iterator> {
+    shared: Rc<RefCell<TeeShared<I>>>,
+    side: usize,
+}
+
+impl<I: Iterator> Iterator for Tee<I>
+where
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let mut shared = self.shared.borrow_mut();
+        if let Some(item) = shared.pending[self.side].pop_front() {
+            return Some(item);
+        }
+        let item = shared.iter.next()?;
+        // 另一侧已经被 drop 时就不必再缓存了
+        if Rc::strong_count(&self.shared) > 1 {
+            shared.pending[1 - self.side].push_back(item.clone());
+        }
+        Some(item)
+    }
+}
+
+pub struct StepByFn<I, F> {
+    iter: I,
+    step: F,
+    skip: usize,
+}
+
+impl<I, F> Iterator for StepByFn<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> usize,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let item = self.iter.nth(self.skip)?;
+        let step = (self.step)(&item);
+        assert!(step > 0, "step must be positive");
+        self.skip = step - 1;
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bench::Rng, learn_iterator::Counter};
+
+    fn random_vec(rng: &mut Rng) -> Vec<u32> {
+        let len = rng.below(20) as usize;
+        (0..len).map(|_| rng.below(4) as u32).collect()
+    }
+
+    // 以下是基于 Vec 的参考实现
+    fn interleave_ref(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut out = Vec::new();
+        for i in 0..a.len().max(b.len()) {
+            out.extend(a.get(i));
+            out.extend(b.get(i));
+        }
+        out
+    }
+
+    fn intersperse_ref(v: &[u32], sep: u32) -> Vec<u32> {
+        let mut out = Vec::new();
+        for (i, x) in v.iter().enumerate() {
+            if i > 0 {
+                out.push(sep);
+            }
+            out.push(*x);
+        }
+        out
+    }
+
+    fn step_by_fn_ref(v: &[u32], step: impl Fn(u32) -> usize) -> Vec<u32> {
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < v.len() {
+            out.push(v[i]);
+            i += step(v[i]);
+        }
+        out
+    }
+
+    #[test]
+    fn adapters_match_vec_references() {
+        let mut rng = Rng::new(19);
+        for _ in 0..300 {
+            let v = random_vec(&mut rng);
+            let w = random_vec(&mut rng);
+            let n = rng.below(4) as usize + 1;
+
+            let chunks: Vec<Vec<u32>> = v.iter().copied().chunks(n).collect();
+            assert_eq!(chunks, v.chunks(n).map(<[u32]>::to_vec).collect::<Vec<_>>());
+            assert_eq!(v.iter().chunks(n).size_hint().0, v.len().div_ceil(n));
+
+            let windows: Vec<Vec<u32>> = v.iter().copied().windows(n).collect();
+            assert_eq!(
+                windows,
+                v.windows(n).map(<[u32]>::to_vec).collect::<Vec<_>>()
+            );
+
+            let interleaved: Vec<u32> = v.iter().copied().interleave(w.clone()).collect();
+            assert_eq!(interleaved, interleave_ref(&v, &w));
+
+            let deduped: Vec<u32> = v.iter().copied().dedup_by_key(|x| x / 2).collect();
+            let mut expected = v.clone();
+            expected.dedup_by_key(|x| *x / 2);
+            assert_eq!(deduped, expected);
+
+            let interspersed: Vec<u32> = IterExt::intersperse(v.iter().copied(), 99).collect();
+            assert_eq!(interspersed, intersperse_ref(&v, 99));
+
+            let groups: Vec<(bool, Vec<u32>)> =
+                v.iter().copied().group_by(|x| x % 2 == 0).collect();
+            let expected: Vec<(bool, Vec<u32>)> = v
+                .chunk_by(|a, b| a % 2 == b % 2)
+                .map(|g| (g[0] % 2 == 0, g.to_vec()))
+                .collect();
+            assert_eq!(groups, expected);
+
+            let stepped: Vec<u32> = v.iter().copied().step_by_fn(|x| *x as usize + 1).collect();
+            assert_eq!(stepped, step_by_fn_ref(&v, |x| x as usize + 1));
+            let constant: Vec<u32> = v.iter().copied().step_by_fn(|_| n).collect();
+            assert_eq!(constant, v.iter().copied().step_by(n).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn tee_buffers_for_the_lagging_side() {
+        let mut rng = Rng::new(20);
+        for _ in 0..100 {
+            let v = random_vec(&mut rng);
+            let (mut a, mut b) = v.iter().copied().tee();
+            // 随机交替推进两侧，两侧最终都得到完整的序列
+            let (mut got_a, mut got_b) = (Vec::new(), Vec::new());
+            loop {
+                let item = if rng.below(2) == 0 {
+                    a.next().map(|x| got_a.push(x))
+                } else {
+                    b.next().map(|x| got_b.push(x))
+                };
+                if item.is_none() {
+                    got_a.extend(a.by_ref());
+                    got_b.extend(b.by_ref());
+                    break;
+                }
+            }
+            assert_eq!(got_a, v);
+            assert_eq!(got_b, v);
+        }
+
+        // 其中一侧被 drop 之后不再缓存
+        let (a, b) = (0..1000).tee();
+        drop(b);
+        assert_eq!(a.sum::<i32>(), 499500);
+    }
+
+    #[test]
+    fn adapters_are_lazy() {
+        let mut pulled = 0;
+        {
+            let mut chunks = (0..).inspect(|_| pulled += 1).chunks(3);
+            assert_eq!(chunks.next(), Some(vec![0, 1, 2]));
+        }
+        assert_eq!(pulled, 3);
+
+        // 内部迭代器是无限的，只要不全部消费也可以使用
+        let firsts: Vec<u32> = (1..).step_by_fn(|x| *x as usize).take(5).collect();
+        assert_eq!(firsts, [1, 2, 4, 8, 16]);
+    }
+
+    #[test]
+    #[should_panic(expected = "step must be positive")]
+    fn step_by_fn_rejects_zero_steps() {
+        (0..3).step_by_fn(|_| 0).for_each(drop);
+    }
+
+    #[test]
+    fn counter_matches_stepped_ranges() {
+        let mut rng = Rng::new(21);
+        for _ in 0..300 {
+            let (start, end, step) = (
+                rng.below(20) as u32,
+                rng.below(30) as u32,
+                rng.below(5) as u32 + 1,
+            );
+            let expected: Vec<u32> = (start..=end).step_by(step as usize).collect();
+            let counter = Counter::range(start, end, step);
+            assert_eq!(counter.len(), expected.len());
+            assert_eq!(counter.clone().collect::<Vec<_>>(), expected);
+            assert_eq!(
+                counter.clone().rev().collect::<Vec<_>>(),
+                expected.iter().rev().copied().collect::<Vec<_>>()
+            );
+
+            // 随机从两端取值，和 Vec 参考实现逐个比较
+            let (mut counter, mut reference) = (counter, expected.clone());
+            loop {
+                let (got, want) = if rng.below(2) == 0 {
+                    let want = if reference.is_empty() {
+                        None
+                    } else {
+                        Some(reference.remove(0))
+                    };
+                    (counter.next(), want)
+                } else {
+                    (counter.next_back(), reference.pop())
+                };
+                assert_eq!(got, want);
+                assert_eq!(counter.len(), reference.len());
+                if got.is_none() {
+                    break;
+                }
+            }
+            // FusedIterator：结束之后一直返回 None
+            assert_eq!((counter.next(), counter.next_back()), (None, None));
+        }
+    }
+
+    // 2^32 个元素的长度只有在 64 位平台上才能用 usize 表示
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn counter_covers_the_full_u32_range() {
+        let mut full = Counter::range(0, u32::MAX, 1);
+        assert_eq!(full.size_hint(), (1usize << 32, Some(1usize << 32)));
+        assert_eq!((full.next(), full.next_back()), (Some(0), Some(u32::MAX)));
+        assert_eq!(full.len(), (1usize << 32) - 2);
+
+        let big_steps = Counter::range(0, u32::MAX, u32::MAX / 2);
+        assert_eq!(
+            big_steps.collect::<Vec<_>>(),
+            [0, u32::MAX / 2, u32::MAX - 1]
+        );
+        assert_eq!(
+            Counter::range(u32::MAX, u32::MAX, 7).collect::<Vec<_>>(),
+            [u32::MAX]
+        );
+    }
+
+    #[test]
+    #[cfg(not(target_pointer_width = "64"))]
+    #[should_panic(expected = "range has more than usize::MAX elements")]
+    fn counter_rejects_ranges_longer_than_usize() {
+        Counter::range(0, u32::MAX, 1);
+    }
+
+    #[test]
+    fn counter_works_with_the_extension() {
+        let pairs: Vec<Vec<u32>> = Counter::new().windows(2).collect();
+        assert_eq!(pairs, [vec![1, 2], vec![2, 3], vec![3, 4], vec![4, 5]]);
+        let mixed: Vec<u32> = Counter::new().interleave(Counter::new().rev()).collect();
+        assert_eq!(mixed, [1, 5, 2, 4, 3, 3, 4, 2, 5, 1]);
+    }
+}
@@ -1,9 +1,8 @@
-#![feature(test)]
+// 迭代器的性能
+// 迭代器是 Rust 的零成本抽象之一，用迭代器求和与手写的下标循环性能几乎一致，甚至因为省掉了边界检查而更快。
+// 对比的基准测试见 benches/performance.rs，通过 `cargo bench` 运行。
 
-extern crate rand;
-extern crate test;
-
-fn sum_for(x: &[f64]) -> f64 {
+pub fn sum_for(x: &[f64]) -> f64 {
     let mut result: f64 = 0.0;
     for i in 0..x.len() {
         result += x[i];
@@ -11,32 +10,18 @@ fn sum_for(x: &[f64]) -> f64 {
     result
 }
 
-fn sum_iter(x: &[f64]) -> f64 {
+pub fn sum_iter(x: &[f64]) -> f64 {
     x.iter().sum::<f64>()
 }
 
 #[cfg(test)]
-mod bench {
+mod tests {
     use super::*;
-    use rand::{thread_rng, Rng};
-    use test::Bencher;
-
-    const LEN: usize = 1024 * 1024;
-
-    fn rand_array(cnt: u32) -> Vec<f64> {
-        let mut rng = thread_rng();
-        (0..cnt).map(|_| rng.gen::<f64>()).collect()
-    }
-
-    #[bench]
-    fn bench_for(b: &mut Bencher) {
-        let samples = rand_array(LEN as u32);
-        b.iter(|| sum_for(&samples))
-    }
+    use crate::bench::Rng;
 
-    #[bench]
-    fn bench_iter(b: &mut Bencher) {
-        let samples = rand_array(LEN as u32);
-        b.iter(|| sum_iter(&samples))
+    #[test]
+    fn sum_for_matches_sum_iter() {
+        let samples = Rng::new(1).f64_vec(1024);
+        assert_eq!(sum_for(&samples), sum_iter(&samples));
     }
 }
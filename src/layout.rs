@@ -0,0 +1,351 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::{Cell, RefCell},
+    fmt::{self, Display},
+    hint::black_box,
+    mem,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    circular_reference_and_self_reference::Node,
+    geometry::Vector2,
+    learn_iterator::{Counter, Shoe},
+    learn_trait::Post,
+    refcell::MsgQueue,
+    trait_obj::Button,
+    units::{Meter, Quantity},
+};
+
+// 内存布局
+// refcell 章节用手写的 Wrapper<T> 解释 Rc<RefCell<T>> 的内存开销，char_bool_unit、learn_string 章节零散地调用了 size_of_val。
+// 这里把这些数字集中起来，对每个类型给出：
+// 1. size_of、align_of：值本身(栈上或者内嵌在其它类型中)占用的字节数和对齐
+// 2. niche：Option<T> 是否和 T 一样大，即编译器能否利用 T 中不可能出现的位模式(例如空指针)来表示 None
+// 3. heap：构造一个示例值时在堆上分配的字节数和次数，overhead 是其中超出数据本身的部分(引用计数、借用标记等)
+// 堆分配由 CountingAlloc 统计，它需要通过 #[global_allocator] 注册，rust_thinking 可执行文件已经注册了它，运行 `rust_thinking layout` 即可看到完整的表格。
+
+thread_local! {
+    // 常量初始化、没有析构函数的线程局部变量不会在访问时分配内存，可以在分配器内部使用
+    static ALLOCATED: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+}
+
+/// 统计当前线程堆分配的全局分配器，实际的分配交给 [`System`]。
+///
+/// ```
+/// use rust_thinking::layout::{measure, Allocation, CountingAlloc};
+///
+/// #[global_allocator]
+/// static ALLOC: CountingAlloc = CountingAlloc;
+///
+/// fn main() {
+///     let (_, heap) = measure(|| Box::new(1u64));
+///     assert_eq!(heap, Some(Allocation { bytes: 8, count: 1 }));
+/// }
+/// ```
+pub struct CountingAlloc;
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // 只统计增长的部分
+        count(new_size.saturating_sub(layout.size()));
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+fn count(bytes: usize) {
+    // 线程退出时线程局部变量可能已经销毁，这时的分配不再统计
+    let _ = ALLOCATED.try_with(|allocated| {
+        let (total, times) = allocated.get();
+        allocated.set((total + bytes, times + 1));
+    });
+}
+
+fn allocated() -> (usize, usize) {
+    ALLOCATED.with(Cell::get)
+}
+
+/// 一段代码在堆上分配的字节数和次数。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Allocation {
+    pub bytes: usize,
+    pub count: usize,
+}
+
+/// [`CountingAlloc`] 是否已经注册为全局分配器。
+pub fn counting_installed() -> bool {
+    let before = allocated();
+    black_box(Box::new(0u8));
+    allocated() != before
+}
+
+/// 运行 `f`，返回它的结果和期间当前线程的堆分配；没有注册 [`CountingAlloc`] 时分配为 `None`。
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<Allocation>) {
+    if !counting_installed() {
+        return (f(), None);
+    }
+    let (bytes, count) = allocated();
+    let value = f();
+    let (bytes_after, count_after) = allocated();
+    let allocation = Allocation {
+        bytes: bytes_after - bytes,
+        count: count_after - count,
+    };
+    (value, Some(allocation))
+}
+
+/// 表格中的一行。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    pub name: &'static str,
+    pub size: usize,
+    pub align: usize,
+    pub niche: bool,
+    pub heap: Option<Allocation>,
+    // 被放到堆上的数据本身的大小，用来计算 overhead
+    pub payload: Option<usize>,
+}
+
+impl Row {
+    /// 构造一个示例值来测量 `T` 的布局，示例值在测量完之后立即释放。
+    pub fn of<T>(name: &'static str, make: impl FnOnce() -> T) -> Row {
+        let (value, heap) = measure(make);
+        drop(black_box(value));
+        Row {
+            name,
+            size: mem::size_of::<T>(),
+            align: mem::align_of::<T>(),
+            niche: mem::size_of::<Option<T>>() == mem::size_of::<T>(),
+            heap,
+            payload: None,
+        }
+    }
+
+    pub fn payload(mut self, bytes: usize) -> Row {
+        self.payload = Some(bytes);
+        self
+    }
+
+    /// 堆分配中超出数据本身的字节数。
+    pub fn overhead(&self) -> Option<usize> {
+        Some(self.heap?.bytes.saturating_sub(self.payload?))
+    }
+}
+
+/// 常见的智能指针组合和各章节中的类型，智能指针中包裹的都是 `u64`。
+pub fn table() -> Vec<Row> {
+    const PAYLOAD: usize = mem::size_of::<u64>();
+    let words: &str = "hello";
+    let shown: &dyn Display = &42u64;
+    vec![
+        Row::of("u64", || 7u64),
+        Row::of("Box<u64>", || Box::new(7u64)).payload(PAYLOAD),
+        Row::of("Rc<u64>", || Rc::new(7u64)).payload(PAYLOAD),
+        Row::of("Arc<u64>", || Arc::new(7u64)).payload(PAYLOAD),
+        Row::of("Cell<u64>", || Cell::new(7u64)),
+        Row::of("RefCell<u64>", || RefCell::new(7u64)),
+        Row::of("Rc<RefCell<u64>>", || Rc::new(RefCell::new(7u64))).payload(PAYLOAD),
+        Row::of("Arc<Mutex<u64>>", || Arc::new(Mutex::new(7u64))).payload(PAYLOAD),
+        Row::of("Option<u64>", || Some(7u64)),
+        Row::of("Option<Box<u64>>", || Some(Box::new(7u64))).payload(PAYLOAD),
+        Row::of("&str", || words),
+        Row::of("String", || String::from(words)).payload(words.len()),
+        Row::of("&dyn Display", || shown),
+        Row::of("Box<dyn Display>", || Box::new(7u64) as Box<dyn Display>).payload(PAYLOAD),
+        Row::of("Box<[u64]> (len 4)", || vec![7u64; 4].into_boxed_slice()).payload(4 * PAYLOAD),
+        Row::of("Vec<u64> (len 4)", || vec![7u64; 4]).payload(4 * PAYLOAD),
+        Row::of("learn_iterator::Shoe", || Shoe {
+            size: 10,
+            style: String::from("boot"),
+        }),
+        Row::of("learn_iterator::Counter", Counter::new),
+        Row::of("learn_trait::Post", || Post {
+            title: String::from("title"),
+            author: String::from("author"),
+            content: String::from("content"),
+        }),
+        Row::of("refcell::MsgQueue", MsgQueue::new),
+        Row::of("trait_obj::Button", || Button {
+            width: 50,
+            height: 10,
+            label: String::from("OK"),
+        }),
+        Row::of("circular_reference::Node", || Node {
+            value: 3,
+            parent: RefCell::new(Default::default()),
+            children: RefCell::new(Vec::new()),
+        }),
+        Row::of("geometry::Vector2<f64>", || Vector2::new(1.0, 2.0)),
+        Row::of("units::Quantity<Meter>", || Quantity::<Meter>::new(1.0)),
+    ]
+}
+
+/// 把表格渲染成对齐的文本。
+pub fn render(rows: &[Row]) -> String {
+    let width = rows.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    let mut out = format!(
+        "{:<width$}  {:>4}  {:>5}  {:>5}  {:>10}  {:>8}\n",
+        "type",
+        "size",
+        "align",
+        "niche",
+        "heap",
+        "overhead",
+        width = width
+    );
+    for row in rows {
+        out.push_str(&format!(
+            "{:<width$}  {}\n",
+            row.name,
+            RowCells(row),
+            width = width
+        ));
+    }
+    out
+}
+
+struct RowCells<'a>(&'a Row);
+
+impl Display for RowCells<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let row = self.0;
+        let heap = match row.heap {
+            Some(Allocation { bytes: 0, .. }) => "-".to_string(),
+            Some(a) => format!("{} B x{}", a.bytes, a.count),
+            None => "?".to_string(),
+        };
+        let overhead = match row.overhead() {
+            Some(bytes) => format!("{} B", bytes),
+            None => "-".to_string(),
+        };
+        write!(
+            f,
+            "{:>4}  {:>5}  {:>5}  {:>10}  {:>8}",
+            row.size,
+            row.align,
+            if row.niche { "yes" } else { "no" },
+            heap,
+            overhead
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 测试二进制单独链接，这里注册的分配器只在测试中生效
+    #[global_allocator]
+    static ALLOC: CountingAlloc = CountingAlloc;
+
+    fn row<'a>(rows: &'a [Row], name: &str) -> &'a Row {
+        rows.iter().find(|r| r.name == name).unwrap()
+    }
+
+    #[test]
+    fn measure_counts_this_thread_only() {
+        assert!(counting_installed());
+        let (v, heap) = measure(|| vec![0u32; 100]);
+        assert_eq!(v.len(), 100);
+        assert_eq!(
+            heap,
+            Some(Allocation {
+                bytes: 400,
+                count: 1
+            })
+        );
+
+        let (_, heap) = measure(|| 1 + 1);
+        assert_eq!(heap, Some(Allocation::default()));
+
+        // 其它线程的分配不计入，这里只统计到创建线程本身的少量分配
+        let (_, heap) = measure(|| {
+            std::thread::scope(|s| {
+                s.spawn(|| black_box(vec![0u8; 1 << 20]).len());
+            })
+        });
+        assert!(heap.unwrap().bytes < 1 << 20);
+    }
+
+    #[test]
+    fn smart_pointer_layouts() {
+        let rows = table();
+        let word = mem::size_of::<usize>();
+        // 计数、借用标记和 u64 放在一起时按两者中较大的对齐补齐，32 位平台上 u64 的对齐可能是 4 也可能是 8
+        let align = mem::align_of::<u64>().max(mem::align_of::<usize>());
+        let padded = |bytes: usize| bytes.next_multiple_of(align);
+
+        // 指针本身都是一个字，并且都有 niche
+        for name in ["Box<u64>", "Rc<u64>", "Arc<u64>", "Rc<RefCell<u64>>"] {
+            let r = row(&rows, name);
+            assert_eq!((r.size, r.niche), (word, true), "{}", name);
+            assert_eq!(r.heap.unwrap().count, 1, "{}", name);
+        }
+        // 胖指针是两个字
+        for name in [
+            "&str",
+            "&dyn Display",
+            "Box<dyn Display>",
+            "Box<[u64]> (len 4)",
+        ] {
+            assert_eq!(row(&rows, name).size, 2 * word, "{}", name);
+        }
+
+        // Box 没有额外开销；Rc/Arc 多了强弱两个计数；Rc<RefCell> 再多一个借用标记，正是 refcell 章节中 Wrapper 的三个字段
+        // 64 位平台上没有补齐，开销分别正好是 2、3 个字
+        let refcell = padded(word + 8);
+        assert_eq!(row(&rows, "Box<u64>").overhead(), Some(0));
+        assert_eq!(
+            row(&rows, "Rc<u64>").overhead(),
+            Some(padded(2 * word + 8) - 8)
+        );
+        assert_eq!(
+            row(&rows, "Arc<u64>").overhead(),
+            Some(padded(2 * word + 8) - 8)
+        );
+        assert_eq!(
+            row(&rows, "Rc<RefCell<u64>>").overhead(),
+            Some(padded(2 * word + refcell) - 8)
+        );
+        assert_eq!(row(&rows, "RefCell<u64>").size, refcell);
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(row(&rows, "Rc<RefCell<u64>>").overhead(), Some(3 * word));
+
+        // u64 的所有位模式都合法，Option 需要额外的判别字段，判别字段按 u64 的对齐补齐
+        assert!(!row(&rows, "u64").niche);
+        assert_eq!(row(&rows, "Option<u64>").size, 8 + mem::align_of::<u64>());
+        assert_eq!(
+            row(&rows, "Option<Box<u64>>").size,
+            row(&rows, "Box<u64>").size
+        );
+
+        assert_eq!(row(&rows, "&str").heap, Some(Allocation::default()));
+        assert_eq!(row(&rows, "learn_trait::Post").heap.unwrap().count, 3);
+        assert_eq!(row(&rows, "geometry::Vector2<f64>").size, 16);
+        assert_eq!(row(&rows, "units::Quantity<Meter>").size, 8);
+    }
+
+    #[test]
+    fn render_aligns_columns() {
+        let rendered = render(&table());
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert!(lines[0].starts_with("type"));
+        assert!(lines.iter().all(|l| l.len() == lines[0].len()));
+        assert!(rendered.contains("Rc<RefCell<u64>>"));
+    }
+}
@@ -1,4 +1,9 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, iter::FusedIterator};
+
+use crate::{
+    iter_ext::IterExt,
+    query::{shoe_catalog, Query},
+};
 
 pub fn iterator() {
     // 迭代器允许我们迭代一个连续的集合，例如数组、动态数组 Vec、HashMap 等，在此过程中，只需关心集合中的元素如何处理，而无需关心如何开始、如何结束、按照什么样的索引去访问等问题。
@@ -136,15 +141,6 @@ pub fn iterator() {
 
     // 闭包作为适配器参数
     // 之前的 map 方法中，我们使用闭包来作为迭代器适配器的参数，它最大的好处不仅在于可以就地实现迭代器中元素的处理，还在于可以捕获环境值:
-    #[derive(Debug)]
-    struct Shoe {
-        size: u32,
-        style: String,
-    }
-    fn shoes_in_size(shoes: Vec<Shoe>, shoe_size: u32) -> Vec<Shoe> {
-        shoes.into_iter().filter(|s| s.size == shoe_size).collect()
-    }
-    // filter 是迭代器适配器，用于对迭代器中的每个值进行过滤。 它使用闭包作为参数，该闭包的参数 s 是来自迭代器中的值，然后使用 s 跟外部环境中的 shoe_size 进行比较，若相等，则在迭代器中保留 s 值，若不相等，则从迭代器中剔除 s 值，最终通过 collect 收集为 Vec<Shoe> 类型。
     let shoe_vec = vec![
         Shoe {
             size: 1,
@@ -158,36 +154,18 @@ pub fn iterator() {
     let processed_shoe = shoes_in_size(shoe_vec, 1);
     println!("{:?}", processed_shoe);
 
+    // 过滤条件多起来之后，可以用 query 模块把过滤、排序、分组写成一份查询计划，再作用到任意 IntoIterator 上
+    let by_style: Vec<(String, usize, f64)> = Query::<Shoe>::new()
+        .filter(|s| s.size >= 9)
+        .group_by(|s| s.style.clone())
+        .select(|g| (g.key.clone(), g.count(), g.avg(|s| s.size).unwrap()))
+        .run(shoe_catalog())
+        .collect();
+    println!("by style: {:?}", by_style);
+
     // 实现 Iterator 特征
     // 之前的内容我们一直基于数组来创建迭代器，实际上，不仅仅是数组，基于其它集合类型一样可以创建迭代器，例如 HashMap。 你也可以创建自己的迭代器 —— 只要为自定义类型实现 Iterator 特征即可。
-    // 首先，创建一个计数器 :
-    struct Counter {
-        count: u32,
-    }
-
-    impl Counter {
-        fn new() -> Counter {
-            Counter { count: 0 }
-        }
-    }
-
-    // 我们为计数器 Counter 实现了一个关联函数 new，用于创建新的计数器实例。下面我们继续为计数器实现 Iterator 特征：
-    impl Iterator for Counter {
-        type Item = u32;
-
-        fn next(&mut self) -> Option<Self::Item> {
-            if self.count < 5 {
-                self.count += 1;
-                Some(self.count)
-            } else {
-                None
-            }
-        }
-    }
-
-    // 首先，将该特征的关联类型设置为 u32，由于我们的计数器保存的 count 字段就是 u32 类型， 因此在 next 方法中，最后返回的是实际上是 Option<u32> 类型。
-    // 每次调用 next 方法，都会让计数器的值加一，然后返回最新的计数值，一旦计数大于 5，就返回 None。
-    // 最后，使用我们新建的 Counter 进行迭代：
+    // 首先，创建一个计数器(Counter 的定义和 Iterator 实现在模块底部)，然后使用我们新建的 Counter 进行迭代：
     let mut counter = Counter::new();
     assert_eq!(counter.next(), Some(1));
     assert_eq!(counter.next(), Some(2));
@@ -211,6 +189,11 @@ pub fn iterator() {
     // 2. map 是将迭代器中的值经过映射后，转换成新的值[2, 6, 12, 20]
     // 3. filter 对迭代器中的元素进行过滤，若闭包返回 true 则保留元素[6, 12]，反之剔除
     // 而 sum 是消费者适配器，对迭代器中的所有元素求和，最终返回一个 u32 值 18。
+    // 反过来，也可以给所有迭代器添加新的适配器：iter_ext 模块中的 IterExt 是一个扩展特征，引入之后任何迭代器都能使用 windows、group_by 等方法
+    let windows: Vec<Vec<u32>> = Counter::range(10, 50, 10).windows(2).collect();
+    println!("windows: {:?}", windows);
+    let rev: Vec<u32> = Counter::new().rev().collect();
+    println!("rev: {:?}, len: {}", rev, Counter::new().len());
 
     // enumerate
     let v = vec![1u64, 2, 3, 4, 5, 6];
@@ -232,3 +215,139 @@ pub fn iterator() {
 
     println!("{}", val);
 }
+
+/// 鞋子，`shoes_in_size` 用它来演示 `filter` 闭包捕获环境中的值。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shoe {
+    pub size: u32,
+    pub style: String,
+}
+
+/// 通过 `into_iter` 拿走 `shoes` 的所有权，只保留尺码等于 `shoe_size` 的鞋子。
+///
+/// ```
+/// use rust_thinking::learn_iterator::{shoes_in_size, Shoe};
+///
+/// let shoes = vec![
+///     Shoe { size: 10, style: String::from("sneaker") },
+///     Shoe { size: 13, style: String::from("sandal") },
+///     Shoe { size: 10, style: String::from("boot") },
+/// ];
+/// let in_my_size = shoes_in_size(shoes, 10);
+/// assert_eq!(in_my_size.len(), 2);
+/// assert!(in_my_size.iter().all(|s| s.size == 10));
+/// ```
+pub fn shoes_in_size(shoes: Vec<Shoe>, shoe_size: u32) -> Vec<Shoe> {
+    shoes.into_iter().filter(|s| s.size == shoe_size).collect()
+}
+// filter 是迭代器适配器，用于对迭代器中的每个值进行过滤。 它使用闭包作为参数，该闭包的参数 s 是来自迭代器中的值，然后使用 s 跟外部环境中的 shoe_size 进行比较，若相等，则在迭代器中保留 s 值，若不相等，则从迭代器中剔除 s 值，最终通过 collect 收集为 Vec<Shoe> 类型。
+
+/// 自定义迭代器，默认从 1 数到 5，也可以用 [`Counter::range`] 指定起点、终点和步长。
+///
+/// ```
+/// use rust_thinking::learn_iterator::Counter;
+///
+/// let v: Vec<u32> = Counter::new().collect();
+/// assert_eq!(v, vec![1, 2, 3, 4, 5]);
+///
+/// // 实现了 next 之后，其它迭代器适配器和消费者方法都可以直接使用
+/// let sum: u32 = Counter::new()
+///     .zip(Counter::new().skip(1))
+///     .map(|(a, b)| a * b)
+///     .filter(|x| x % 3 == 0)
+///     .sum();
+/// assert_eq!(18, sum);
+///
+/// // 实现了 DoubleEndedIterator 和 ExactSizeIterator 之后，还可以使用 rev 和 len
+/// let mut evens = Counter::range(0, 10, 2);
+/// assert_eq!(evens.len(), 6);
+/// assert_eq!(evens.next_back(), Some(10));
+/// assert_eq!(evens.rev().collect::<Vec<_>>(), [8, 6, 4, 2, 0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Counter {
+    start: u32,
+    step: u32,
+    // 还没有产出的元素下标范围 [front, back)，第 i 个元素是 start + i * step
+    // range(0, u32::MAX, 1) 有 2^32 个元素，u32 放不下，所以下标用 u64
+    front: u64,
+    back: u64,
+}
+
+impl Counter {
+    pub fn new() -> Counter {
+        Counter::range(1, 5, 1)
+    }
+
+    // 从 start 开始，每次增加 step，直到超过 end(包含 end)
+    // 元素个数必须能用 usize 表示，否则无法实现 ExactSizeIterator；16/32 位平台上 range(0, u32::MAX, 1) 会 panic
+    pub fn range(start: u32, end: u32, step: u32) -> Counter {
+        assert!(step > 0, "step must be positive");
+        let back = if end < start {
+            0
+        } else {
+            u64::from((end - start) / step) + 1
+        };
+        assert!(
+            usize::try_from(back).is_ok(),
+            "range has more than usize::MAX elements"
+        );
+        Counter {
+            start,
+            step,
+            front: 0,
+            back,
+        }
+    }
+
+    // i < back，结果不会超过 end
+    fn nth_value(&self, i: u64) -> u32 {
+        (u64::from(self.start) + i * u64::from(self.step)) as u32
+    }
+}
+
+impl Default for Counter {
+    fn default() -> Counter {
+        Counter::new()
+    }
+}
+
+// 我们为计数器 Counter 实现了关联函数 new 和 range，用于创建新的计数器实例。下面我们继续为计数器实现 Iterator 特征：
+impl Iterator for Counter {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front < self.back {
+            self.front += 1;
+            Some(self.nth_value(self.front - 1))
+        } else {
+            None
+        }
+    }
+
+    // 剩余元素的个数是确定的，ExactSizeIterator 依赖这里返回的精确值；range 已经保证它能放进 usize
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) as usize;
+        (len, Some(len))
+    }
+}
+
+// 从尾部取值：back 向前移动，和 front 相遇时两端都结束
+impl DoubleEndedIterator for Counter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front < self.back {
+            self.back -= 1;
+            Some(self.nth_value(self.back))
+        } else {
+            None
+        }
+    }
+}
+
+impl ExactSizeIterator for Counter {}
+
+// 结束之后再调用 next 依然返回 None，实现 FusedIterator 之后 fuse() 不会再额外包一层判断
+impl FusedIterator for Counter {}
+
+// 首先，将该特征的关联类型设置为 u32，由于计数器产出的每个值都是 u32 类型，因此在 next 方法中，最后返回的是实际上是 Option<u32> 类型。
+// 每次调用 next 方法，都会让 front 加一，然后返回对应的计数值，一旦 front 追上 back，就返回 None。
@@ -7,35 +7,7 @@ pub fn learn_trait() {
     // 如果不同的类型具有相同的行为，那么我们就可以定义一个特征，然后为这些类型实现该特征。定义特征是把一些方法组合在一起，目的是定义一个实现某些目标所必需的行为的集合。
 
     // 特征只定义行为看起来是什么样的，而不定义行为具体是怎么样的。因此，我们只定义特征方法的签名，而不进行实现，此时方法签名结尾是 ;，而不是一个 {}。
-    pub trait Summary {
-        fn summarize(&self) -> String;
-    }
-
-    // 为类型实现特征
-    pub struct Post {
-        pub title: String,   // 标题
-        pub author: String,  // 作者
-        pub content: String, // 内容
-    }
-
-    impl Summary for Post {
-        fn summarize(&self) -> String {
-            format!("文章{}, 作者是{}", self.title, self.author)
-        }
-    }
-
-    #[derive(Debug)]
-    pub struct Weibo {
-        pub username: String,
-        pub content: String,
-    }
-
-    impl Summary for Weibo {
-        fn summarize(&self) -> String {
-            format!("{}发表了微博{}", self.username, self.content)
-        }
-    }
-
+    // Summary 特征以及实现了它的 Post、Weibo 定义在模块顶层，方便其它章节复用
     let post = Post {
         title: "Rust语言简介".to_string(),
         author: "Sunface".to_string(),
@@ -208,3 +180,50 @@ pub fn learn_trait() {
         println!("Ten is less than one hundred.");
     }
 }
+
+/// 摘要特征：只定义方法签名，由具体类型提供实现。
+///
+/// ```
+/// use rust_thinking::learn_trait::{Post, Summary, Weibo};
+///
+/// let post = Post {
+///     title: "Rust语言简介".to_string(),
+///     author: "Sunface".to_string(),
+///     content: "Rust棒极了!".to_string(),
+/// };
+/// let weibo = Weibo {
+///     username: "sunface".to_string(),
+///     content: "好像微博没Tweet好用".to_string(),
+/// };
+///
+/// assert_eq!(post.summarize(), "文章Rust语言简介, 作者是Sunface");
+/// assert_eq!(weibo.summarize(), "sunface发表了微博好像微博没Tweet好用");
+/// ```
+pub trait Summary {
+    fn summarize(&self) -> String;
+}
+
+// 为类型实现特征
+pub struct Post {
+    pub title: String,   // 标题
+    pub author: String,  // 作者
+    pub content: String, // 内容
+}
+
+impl Summary for Post {
+    fn summarize(&self) -> String {
+        format!("文章{}, 作者是{}", self.title, self.author)
+    }
+}
+
+#[derive(Debug)]
+pub struct Weibo {
+    pub username: String,
+    pub content: String,
+}
+
+impl Summary for Weibo {
+    fn summarize(&self) -> String {
+        format!("{}发表了微博{}", self.username, self.content)
+    }
+}
@@ -0,0 +1,121 @@
+//! Rust-Thinking: Rust 学习笔记，每个模块对应一个章节。
+//!
+//! 章节入口函数通过 [`registry::CHAPTERS`] 注册，`rust_thinking` 可执行文件只是在这张表上做了一层命令行封装。
+
+// 各章节为了演示语法，刻意保留了未使用的变量、非惯用的写法以及只声明不调用的条目，
+// 这些 lint 只对这样的章节放开，其余模块(包括后来新增的工具模块)仍然按默认的 lint 检查
+macro_rules! chapters {
+    ($($name:ident),* $(,)?) => {
+        $(
+            #[allow(
+                dead_code,
+                unused,
+                nonstandard_style,
+                static_mut_refs,
+                redundant_semicolons,
+                noop_method_call,
+                non_local_definitions,
+                mismatched_lifetime_syntaxes,
+                clippy::assign_op_pattern,
+                clippy::char_lit_as_u8,
+                clippy::disallowed_names,
+                clippy::extra_unused_lifetimes,
+                clippy::get_first,
+                clippy::let_unit_value,
+                clippy::manual_is_ascii_check,
+                clippy::manual_map,
+                clippy::manual_range_patterns,
+                clippy::manual_slice_fill,
+                clippy::match_single_binding,
+                clippy::needless_borrow,
+                clippy::needless_lifetimes,
+                clippy::needless_range_loop,
+                clippy::needless_return,
+                clippy::print_literal,
+                clippy::ptr_arg,
+                clippy::question_mark,
+                clippy::redundant_pattern_matching,
+                clippy::single_match,
+                clippy::unnecessary_fallible_conversions,
+                clippy::unnecessary_fold,
+                clippy::unused_unit,
+                clippy::useless_conversion,
+                clippy::useless_vec,
+                clippy::while_let_loop,
+            )]
+            pub mod $name;
+        )*
+    };
+}
+
+pub mod basic_types;
+pub mod bench;
+pub mod broker;
+pub mod cache;
+pub mod char_bool_unit;
+pub mod container;
+pub mod deep_trait;
+pub mod destructuring_assignment;
+pub mod enumerations_and_integers;
+pub mod error_handling;
+pub mod fn_combinators;
+pub mod format_output;
+pub mod function;
+pub mod geometry;
+pub mod in_place;
+pub mod iter_ext;
+pub mod layout;
+pub mod learn_struct;
+pub mod memo;
+pub mod mock;
+pub mod notes_and_documentation;
+pub mod outline;
+pub mod panic_tools;
+pub mod query;
+pub mod reference;
+pub mod registry;
+pub mod runner;
+pub mod rust_common_pitfalls;
+pub mod snapshot;
+pub mod statements_expressions;
+pub mod tuple;
+pub mod units;
+
+chapters! {
+    circular_reference_and_self_reference,
+    closure,
+    complex_types,
+    deep_lifecycle,
+    eq_partialeq,
+    generics,
+    global_variable,
+    hash_map,
+    iterator_performance,
+    learn_box,
+    learn_deref,
+    learn_drop,
+    learn_iterator,
+    learn_string,
+    learn_trait,
+    learn_vec,
+    lifecycle,
+    lifecycle_exp_1,
+    lifecycle_exp_2,
+    method,
+    newtype_and_type_aliases,
+    option,
+    pattern_matching_1,
+    pattern_matching_2,
+    pattern_matching_3,
+    pattern_matching_4,
+    process_control,
+    rc_arc,
+    refcell,
+    return_values_and_error_handling,
+    self_referential_struct,
+    sized_dst,
+    static_lifecycle,
+    trait_obj,
+    type_conversion,
+    unsafe_rust,
+}
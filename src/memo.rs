@@ -0,0 +1,391 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+// 按参数缓存的记忆化工具
+// closure 章节里的 Cacher1 只能缓存一个值，这里用 HashMap 以参数为 key 缓存每一个结果：
+// 1. Memo：单线程版本，可以设置容量，超出容量时淘汰最久未使用(LRU)的结果
+// 2. SyncMemo：线程安全版本，多个线程同时请求同一个参数时只会计算一次
+
+/// 缓存的命中统计。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl MemoStats {
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+// 带可选容量的 LRU 表：每次访问都给条目分配一个递增的序号，序号最小的就是最久未使用的条目
+struct Lru<K, V> {
+    entries: HashMap<K, (V, u64)>,
+    order: BTreeMap<u64, K>,
+    tick: u64,
+    capacity: Option<usize>,
+}
+
+impl<K: Hash + Eq + Clone, V> Lru<K, V> {
+    fn new(capacity: Option<usize>) -> Lru<K, V> {
+        assert!(capacity != Some(0), "capacity must be positive");
+        Lru {
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+            capacity,
+        }
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    // 命中时把条目标记为最近使用
+    fn get(&mut self, key: &K) -> Option<&V> {
+        let tick = self.next_tick();
+        let (value, last) = self.entries.get_mut(key)?;
+        self.order.remove(last);
+        self.order.insert(tick, key.clone());
+        *last = tick;
+        Some(value)
+    }
+
+    // 插入新条目，返回因超出容量被淘汰的条目数
+    fn insert(&mut self, key: K, value: V) -> u64 {
+        self.remove(&key);
+        let mut evicted = 0;
+        if let Some(capacity) = self.capacity {
+            while self.entries.len() >= capacity {
+                let (_, oldest) = self.order.pop_first().expect("order out of sync");
+                self.entries.remove(&oldest);
+                evicted += 1;
+            }
+        }
+        let tick = self.next_tick();
+        self.order.insert(tick, key.clone());
+        self.entries.insert(key, (value, tick));
+        evicted
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        let (value, tick) = self.entries.remove(key)?;
+        self.order.remove(&tick);
+        Some(value)
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+/// 以参数为 key 缓存 `f` 的计算结果，每个不同的参数只计算一次。
+///
+/// ```
+/// use rust_thinking::memo::Memo;
+///
+/// let mut calls = 0;
+/// let mut square = Memo::new(|x: &u64| {
+///     calls += 1;
+///     x * x
+/// });
+/// assert_eq!(square.get(3), 9);
+/// assert_eq!(square.get(4), 16);
+/// assert_eq!(square.get(3), 9);
+/// assert_eq!(square.stats().hits, 1);
+/// assert_eq!(square.stats().misses, 2);
+/// drop(square);
+/// assert_eq!(calls, 2);
+/// ```
+pub struct Memo<F, K, V> {
+    f: F,
+    cache: Lru<K, V>,
+    stats: MemoStats,
+}
+
+impl<F, K, V> Memo<F, K, V>
+where
+    F: FnMut(&K) -> V,
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    // 不限容量
+    pub fn new(f: F) -> Memo<F, K, V> {
+        Memo {
+            f,
+            cache: Lru::new(None),
+            stats: MemoStats::default(),
+        }
+    }
+
+    // 最多缓存 capacity 个结果，超出时淘汰最久未使用的结果
+    pub fn with_capacity(f: F, capacity: usize) -> Memo<F, K, V> {
+        Memo {
+            f,
+            cache: Lru::new(Some(capacity)),
+            stats: MemoStats::default(),
+        }
+    }
+
+    pub fn get(&mut self, key: K) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+        self.stats.misses += 1;
+        let value = (self.f)(&key);
+        self.stats.evictions += self.cache.insert(key, value.clone());
+        value
+    }
+
+    // 丢弃某个参数的缓存结果，下次访问时重新计算
+    pub fn invalidate(&mut self, key: &K) -> Option<V> {
+        self.cache.remove(key)
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.cache.entries.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.entries.is_empty()
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+}
+
+struct SyncState<K, V> {
+    cache: Lru<K, Arc<OnceLock<V>>>,
+    stats: MemoStats,
+}
+
+/// 线程安全的 [`Memo`]，可以通过 `&self` 在多个线程间共享。
+///
+/// 缓存表只在查找和插入时短暂加锁，真正的计算在锁外进行：每个参数对应一个 `OnceLock`，
+/// 多个线程同时请求同一个参数时，只有一个线程执行计算，其它线程等待结果；不同参数的计算可以并行。
+///
+/// ```
+/// use std::thread;
+/// use rust_thinking::memo::SyncMemo;
+///
+/// let memo = SyncMemo::new(|n: &u64| (1..=*n).product::<u64>());
+/// thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| assert_eq!(memo.get(10), 3628800));
+///     }
+/// });
+/// assert_eq!(memo.stats().misses, 1);
+/// ```
+pub struct SyncMemo<F, K, V> {
+    f: F,
+    state: Mutex<SyncState<K, V>>,
+}
+
+impl<F, K, V> SyncMemo<F, K, V>
+where
+    F: Fn(&K) -> V + Sync,
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new(f: F) -> SyncMemo<F, K, V> {
+        SyncMemo::build(f, None)
+    }
+
+    pub fn with_capacity(f: F, capacity: usize) -> SyncMemo<F, K, V> {
+        SyncMemo::build(f, Some(capacity))
+    }
+
+    fn build(f: F, capacity: Option<usize>) -> SyncMemo<F, K, V> {
+        SyncMemo {
+            f,
+            state: Mutex::new(SyncState {
+                cache: Lru::new(capacity),
+                stats: MemoStats::default(),
+            }),
+        }
+    }
+
+    pub fn get(&self, key: K) -> V {
+        let cell = {
+            let mut state = self.state.lock().unwrap();
+            match state.cache.get(&key).cloned() {
+                Some(cell) => {
+                    state.stats.hits += 1;
+                    cell
+                }
+                None => {
+                    let cell = Arc::new(OnceLock::new());
+                    state.stats.misses += 1;
+                    state.stats.evictions += state.cache.insert(key.clone(), Arc::clone(&cell));
+                    cell
+                }
+            }
+        };
+        cell.get_or_init(|| (self.f)(&key)).clone()
+    }
+
+    pub fn invalidate(&self, key: &K) -> Option<V> {
+        let cell = self.state.lock().unwrap().cache.remove(key)?;
+        cell.get().cloned()
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().unwrap().cache.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().cache.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.state.lock().unwrap().stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        cell::RefCell,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Barrier,
+        },
+        thread,
+    };
+
+    #[test]
+    fn computes_once_per_distinct_key() {
+        let calls = RefCell::new(Vec::new());
+        let mut memo = Memo::new(|s: &String| {
+            calls.borrow_mut().push(s.clone());
+            s.len()
+        });
+        for word in ["a", "bb", "a", "ccc", "bb", "a"] {
+            assert_eq!(memo.get(word.to_string()), word.len());
+        }
+        assert_eq!(memo.len(), 3);
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 3,
+                misses: 3,
+                evictions: 0
+            }
+        );
+        assert_eq!(memo.stats().hit_rate(), 0.5);
+        drop(memo);
+        assert_eq!(*calls.borrow(), ["a", "bb", "ccc"]);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut computed = Vec::new();
+        let mut memo = Memo::with_capacity(
+            |x: &i32| {
+                computed.push(*x);
+                x * 10
+            },
+            2,
+        );
+        memo.get(1);
+        memo.get(2);
+        memo.get(1); // 1 变为最近使用
+        memo.get(3); // 淘汰 2
+        assert!(memo.contains(&1) && memo.contains(&3) && !memo.contains(&2));
+        memo.get(2); // 重新计算 2，淘汰 1
+        assert!(!memo.contains(&1));
+        assert_eq!(memo.len(), 2);
+        assert_eq!(memo.stats().evictions, 2);
+        drop(memo);
+        assert_eq!(computed, vec![1, 2, 3, 2]);
+    }
+
+    #[test]
+    fn invalidate_forces_recompute() {
+        let mut version = 0;
+        let mut memo = Memo::new(|_: &&str| {
+            version += 1;
+            version
+        });
+        assert_eq!(memo.get("config"), 1);
+        assert_eq!(memo.get("config"), 1);
+        assert_eq!(memo.invalidate(&"config"), Some(1));
+        assert_eq!(memo.invalidate(&"config"), None);
+        assert_eq!(memo.get("config"), 2);
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.get("config"), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity must be positive")]
+    fn zero_capacity_is_rejected() {
+        Memo::with_capacity(|x: &u8| *x, 0);
+    }
+
+    #[test]
+    fn sync_memo_computes_each_key_once_across_threads() {
+        let calls = AtomicUsize::new(0);
+        let memo = SyncMemo::new(|x: &u32| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            thread::sleep(std::time::Duration::from_millis(5));
+            x * 2
+        });
+        let barrier = Barrier::new(8);
+        thread::scope(|s| {
+            for i in 0..8u32 {
+                let (memo, barrier) = (&memo, &barrier);
+                s.spawn(move || {
+                    barrier.wait();
+                    assert_eq!(memo.get(i % 2), (i % 2) * 2);
+                });
+            }
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        let stats = memo.stats();
+        assert_eq!((stats.hits, stats.misses), (6, 2));
+
+        assert_eq!(memo.invalidate(&1), Some(2));
+        assert_eq!(memo.get(1), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn sync_memo_respects_capacity() {
+        let memo = SyncMemo::with_capacity(|x: &u8| u32::from(*x) + 1, 3);
+        for x in 0..10 {
+            memo.get(x);
+        }
+        assert_eq!(memo.len(), 3);
+        assert_eq!(memo.stats().evictions, 7);
+        memo.clear();
+        assert!(memo.is_empty());
+    }
+}
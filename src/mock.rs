@@ -0,0 +1,355 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    fmt::Debug,
+};
+
+// 基于内部可变性的 mock 工具
+// refcell 章节的 MsgQueue 演示了经典的 mock 写法：特征方法只拿到 &self，于是用 RefCell 把调用记录下来。
+// Recorder 把这个写法抽成可复用的部件，嵌入到 mock 类型中，每个被 mock 的方法对应一个 Recorder：
+// 1. call 记录参数并返回预设的返回值，返回值可以按调用序号(从 0 开始)分别设置，也可以设置一个兜底值，都没有时返回 Ret::default()
+// 2. strict 模式下，既没有按序号设置返回值、也没有兜底值的调用被视为意外调用，由 assert_no_unexpected_calls 报告
+// 3. assert_called_times、assert_called_with 等断言失败时会列出所有调用记录，并通过 #[track_caller] 把 panic 位置指向测试代码
+// Args 是参数元组，没有参数时用 ()；Ret 默认为 ()，对应没有返回值的方法。
+
+/// 记录调用参数、按调用序号返回预设值的 mock 部件。
+///
+/// ```
+/// use rust_thinking::{
+///     mock::Recorder,
+///     refcell::{LimitTracker, Messenger},
+/// };
+///
+/// struct MockMessenger {
+///     send: Recorder<String>,
+/// }
+///
+/// impl Messenger for MockMessenger {
+///     fn send(&self, msg: String) {
+///         self.send.call(msg)
+///     }
+/// }
+///
+/// let mock = MockMessenger { send: Recorder::new("send") };
+/// let mut tracker = LimitTracker::new(&mock, 100);
+/// tracker.set_value(50);
+/// tracker.set_value(80);
+///
+/// mock.send.assert_called_times(1);
+/// mock.send.assert_called_with(&"警告：额度已使用 75% 以上".to_string());
+/// ```
+pub struct Recorder<Args, Ret = ()> {
+    name: String,
+    calls: RefCell<Vec<Args>>,
+    stubs: RefCell<BTreeMap<usize, Ret>>,
+    fallback: RefCell<Option<Ret>>,
+    strict: Cell<bool>,
+    unexpected: RefCell<Vec<usize>>,
+}
+
+impl<Args, Ret> Recorder<Args, Ret> {
+    // name 只用于断言失败时的提示信息
+    pub fn new(name: &str) -> Recorder<Args, Ret> {
+        Recorder {
+            name: name.to_string(),
+            calls: RefCell::new(Vec::new()),
+            stubs: RefCell::new(BTreeMap::new()),
+            fallback: RefCell::new(None),
+            strict: Cell::new(false),
+            unexpected: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// 打开 strict 模式：没有预设返回值的调用都算作意外调用。
+    pub fn strict(self) -> Recorder<Args, Ret> {
+        self.strict.set(true);
+        self
+    }
+
+    /// 设置第 `index` 次调用(从 0 开始)的返回值，同时把这次调用标记为预期中的调用。
+    pub fn returns_on(&self, index: usize, ret: Ret) -> &Recorder<Args, Ret> {
+        self.stubs.borrow_mut().insert(index, ret);
+        self
+    }
+
+    /// 设置没有按序号预设时的返回值，设置之后 strict 模式也不会再报告意外调用。
+    pub fn returns(&self, ret: Ret) -> &Recorder<Args, Ret> {
+        *self.fallback.borrow_mut() = Some(ret);
+        self
+    }
+
+    pub fn call_count(&self) -> usize {
+        self.calls.borrow().len()
+    }
+
+    /// 清空调用记录和意外调用，预设的返回值保持不变。
+    pub fn reset(&self) {
+        self.calls.borrow_mut().clear();
+        self.unexpected.borrow_mut().clear();
+    }
+}
+
+impl<Args, Ret: Clone + Default> Recorder<Args, Ret> {
+    /// 记录一次调用，返回预设的返回值。
+    pub fn call(&self, args: Args) -> Ret {
+        let index = {
+            let mut calls = self.calls.borrow_mut();
+            calls.push(args);
+            calls.len() - 1
+        };
+        if let Some(ret) = self.stubs.borrow().get(&index) {
+            return ret.clone();
+        }
+        if let Some(ret) = self.fallback.borrow().as_ref() {
+            return ret.clone();
+        }
+        if self.strict.get() {
+            self.unexpected.borrow_mut().push(index);
+        }
+        Ret::default()
+    }
+}
+
+impl<Args: Clone, Ret> Recorder<Args, Ret> {
+    pub fn calls(&self) -> Vec<Args> {
+        self.calls.borrow().clone()
+    }
+}
+
+impl<Args: Debug, Ret> Recorder<Args, Ret> {
+    #[track_caller]
+    pub fn assert_called_times(&self, n: usize) {
+        let calls = self.calls.borrow();
+        assert!(
+            calls.len() == n,
+            "{}: expected {} call(s), got {}: {:?}",
+            self.name,
+            n,
+            calls.len(),
+            calls
+        );
+    }
+
+    #[track_caller]
+    pub fn assert_not_called(&self) {
+        self.assert_called_times(0);
+    }
+
+    #[track_caller]
+    pub fn assert_no_unexpected_calls(&self) {
+        let unexpected = self.unexpected.borrow();
+        if unexpected.is_empty() {
+            return;
+        }
+        let calls = self.calls.borrow();
+        let details: Vec<String> = unexpected
+            .iter()
+            .map(|&i| format!("#{} {:?}", i, calls[i]))
+            .collect();
+        panic!("{}: unexpected call(s): {}", self.name, details.join(", "));
+    }
+}
+
+impl<Args: Debug + PartialEq, Ret> Recorder<Args, Ret> {
+    /// 断言至少有一次调用的参数等于 `args`。
+    #[track_caller]
+    pub fn assert_called_with(&self, args: &Args) {
+        let calls = self.calls.borrow();
+        assert!(
+            calls.contains(args),
+            "{}: expected a call with {:?}, got {:?}",
+            self.name,
+            args,
+            calls
+        );
+    }
+
+    /// 断言第 `index` 次调用(从 0 开始)的参数等于 `args`。
+    #[track_caller]
+    pub fn assert_nth_called_with(&self, index: usize, args: &Args) {
+        let calls = self.calls.borrow();
+        assert!(
+            calls.get(index) == Some(args),
+            "{}: expected call #{} with {:?}, got {:?}",
+            self.name,
+            index,
+            args,
+            calls
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        learn_trait::Summary,
+        refcell::{LimitTracker, Messenger},
+        trait_obj::{Draw1, Screen},
+    };
+    use std::rc::Rc;
+
+    struct MockMessenger {
+        send: Recorder<String>,
+    }
+
+    impl Messenger for MockMessenger {
+        fn send(&self, msg: String) {
+            self.send.call(msg)
+        }
+    }
+
+    struct MockSummary {
+        summarize: Recorder<(), String>,
+    }
+
+    impl Summary for MockSummary {
+        fn summarize(&self) -> String {
+            self.summarize.call(())
+        }
+    }
+
+    // 多个组件共用一个 Recorder，参数记录组件的名字，可以检查 Screen 的绘制顺序
+    struct MockDraw {
+        name: &'static str,
+        draw: Rc<Recorder<&'static str>>,
+    }
+
+    impl Draw1 for MockDraw {
+        fn draw(&self) {
+            self.draw.call(self.name)
+        }
+    }
+
+    fn headline(items: &[&dyn Summary]) -> String {
+        items
+            .iter()
+            .map(|item| item.summarize())
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    #[test]
+    fn limit_tracker_sends_the_right_warnings() {
+        let mock = MockMessenger {
+            send: Recorder::new("send"),
+        };
+        let mut tracker = LimitTracker::new(&mock, 100);
+        tracker.set_value(10);
+        mock.send.assert_not_called();
+
+        tracker.set_value(75);
+        tracker.set_value(95);
+        tracker.set_value(120);
+        mock.send.assert_called_times(3);
+        mock.send
+            .assert_nth_called_with(0, &"警告：额度已使用 75% 以上".to_string());
+        mock.send
+            .assert_nth_called_with(1, &"紧急：额度已使用 90% 以上".to_string());
+        mock.send
+            .assert_called_with(&"错误：额度已经用完".to_string());
+
+        mock.send.reset();
+        tracker.set_value(0);
+        mock.send.assert_not_called();
+    }
+
+    #[test]
+    fn summary_stubs_per_call_index() {
+        let first = MockSummary {
+            summarize: Recorder::new("summarize"),
+        };
+        first
+            .summarize
+            .returns_on(0, "第一次".to_string())
+            .returns_on(2, "第三次".to_string())
+            .returns("其它".to_string());
+        let second = MockSummary {
+            summarize: Recorder::new("summarize"),
+        };
+
+        assert_eq!(headline(&[&first, &second]), "第一次 | ");
+        assert_eq!(first.summarize(), "其它");
+        assert_eq!(first.summarize(), "第三次");
+        // 没有按序号设置的调用都使用兜底值
+        assert_eq!(first.summarize(), "其它");
+        first.summarize.assert_called_times(4);
+        second.summarize.assert_called_times(1);
+    }
+
+    #[test]
+    fn screen_draws_components_in_order() {
+        let draw = Rc::new(Recorder::new("draw"));
+        let screen = Screen {
+            components: ["header", "body", "footer"]
+                .into_iter()
+                .map(|name| {
+                    Box::new(MockDraw {
+                        name,
+                        draw: draw.clone(),
+                    }) as Box<dyn Draw1>
+                })
+                .collect(),
+        };
+        screen.run();
+        screen.run();
+        draw.assert_called_times(6);
+        assert_eq!(
+            draw.calls(),
+            ["header", "body", "footer", "header", "body", "footer"]
+        );
+    }
+
+    #[test]
+    fn reset_keeps_stubs_for_the_next_round() {
+        let mock = MockSummary {
+            summarize: Recorder::new("summarize"),
+        };
+        mock.summarize.returns_on(0, "first".to_string());
+        assert_eq!(mock.summarize(), "first");
+        assert_eq!(mock.summarize(), "");
+
+        // reset 之后调用序号从 0 重新开始，按序号设置的返回值仍然有效
+        mock.summarize.reset();
+        mock.summarize.assert_not_called();
+        assert_eq!(mock.summarize(), "first");
+        assert_eq!(mock.summarize(), "");
+        mock.summarize.assert_called_times(2);
+    }
+
+    #[test]
+    fn strict_mode_reports_unexpected_calls() {
+        let mock = MockSummary {
+            summarize: Recorder::new("summarize").strict(),
+        };
+        mock.summarize.returns_on(0, "ok".to_string());
+        assert_eq!(mock.summarize(), "ok");
+        mock.summarize.assert_no_unexpected_calls();
+        assert_eq!(mock.summarize(), "");
+
+        let message = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mock.summarize.assert_no_unexpected_calls()
+        }))
+        .unwrap_err();
+        assert_eq!(
+            message.downcast_ref::<String>().unwrap(),
+            "summarize: unexpected call(s): #1 ()"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "send: expected 2 call(s), got 1: [\"hi\"]")]
+    fn assert_called_times_lists_calls() {
+        let recorder: Recorder<&str> = Recorder::new("send");
+        recorder.call("hi");
+        recorder.assert_called_times(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a call with (1, 'b'), got [(1, 'a')]")]
+    fn assert_called_with_compares_argument_tuples() {
+        let recorder: Recorder<(u32, char), bool> = Recorder::new("insert");
+        assert!(!recorder.call((1, 'a')));
+        recorder.assert_called_with(&(1, 'b'));
+    }
+}
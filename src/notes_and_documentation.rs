@@ -10,19 +10,8 @@ pub fn notes_and_documentation() {
     // 当查看一个 crates.io 上的包时，往往需要通过它提供的文档来浏览相关的功能特性、使用方式，这种文档就是通过文档注释实现的。
     // Rust 提供了 cargo doc 的命令，可以用于把这些文档注释转换成 HTML 网页文件，最终展示给用户浏览，这样用户就知道这个包是做什么的以及该如何使用。
 
-    /// `add_one` 将指定值加1
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// let arg = 5;
-    /// let answer = my_crate::add_one(arg);
-    ///
-    /// assert_eq!(6, answer);
-    /// ```
-    pub fn add_one(x: i32) -> i32 {
-        x + 1
-    }
+    // 例如本模块顶层的 add_one 函数：
+    assert_eq!(add_one(5), 6);
 
     // 以上代码有几点需要注意：
     // 1. 文档注释需要位于 lib 类型的包中，例如 src/lib.rs 中
@@ -30,17 +19,8 @@ pub fn notes_and_documentation() {
     // 3. 被注释的对象需要使用 pub 对外可见，记住：文档注释是给用户看的，内部实现细节不应该被暴露出去
 
     // 文档块注释 /** ... */
-    /** `add_two` 将指定值加2
-    ```
-    let arg = 5;
-    let answer = my_crate::add_two(arg);
-
-    assert_eq!(7, answer);
-    ```
-    */
-    pub fn add_two(x: i32) -> i32 {
-        x + 2
-    }
+    // 同样定义在模块顶层的 add_two：
+    assert_eq!(add_two(5), 7);
 
     // 常用文档标题
     // 之前我们见到了在文档注释中该如何使用 markdown，其中包括 # Examples 标题。除了这个标题，还有一些常用的，你可以在项目中酌情使用：
@@ -48,3 +28,29 @@ pub fn notes_and_documentation() {
     // 2. Errors：描述可能出现的错误及什么情况会导致错误，有助于调用者针对不同的错误采取不同的处理方式
     // 3. Safety：如果函数使用 unsafe 代码，那么调用者就需要注意一些使用条件，以确保 unsafe 代码块的正常工作
 }
+
+/// `add_one` 将指定值加1
+///
+/// # Examples
+///
+/// ```
+/// let arg = 5;
+/// let answer = rust_thinking::notes_and_documentation::add_one(arg);
+///
+/// assert_eq!(6, answer);
+/// ```
+pub fn add_one(x: i32) -> i32 {
+    x + 1
+}
+
+/** `add_two` 将指定值加2
+```
+let arg = 5;
+let answer = rust_thinking::notes_and_documentation::add_two(arg);
+
+assert_eq!(7, answer);
+```
+*/
+pub fn add_two(x: i32) -> i32 {
+    x + 2
+}
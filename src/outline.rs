@@ -0,0 +1,373 @@
+use std::fmt::Display;
+
+// 带边框的文本框
+// deep_trait 章节里的 OutlinePrint 用 output.len() 计算边框长度，len 是字节数，遇到中文就会画歪，也处理不了换行。
+// 这里把它做成一个真正的文本框渲染器：
+// 1. 按终端显示宽度计算长度：中日韩文字、全角符号、大部分 emoji 占两列，组合字符占零列
+// 2. 多行输出逐行对齐，可以设置最大宽度让过长的行自动折行
+// 3. 支持 ASCII、单线、双线、圆角四种边框，以及内边距和标题
+// 4. 通过 impl<T: Display> OutlinePrint for T，所有实现了 Display 的类型都可以直接使用
+
+// 宽字符所在的区间(East Asian Wide/Fullwidth 的常用部分)
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE30, 0xFE4F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x1F300, 0x1F64F),
+    (0x1F900, 0x1F9FF),
+    (0x20000, 0x2FFFD),
+    (0x30000, 0x3FFFD),
+];
+
+// 不占宽度的字符：组合附加符号、零宽字符和变体选择符
+const ZERO: &[(u32, u32)] = &[
+    (0x0300, 0x036F),
+    (0x200B, 0x200F),
+    (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F),
+];
+
+fn in_ranges(c: char, ranges: &[(u32, u32)]) -> bool {
+    let c = c as u32;
+    ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi)
+}
+
+/// 单个字符在终端中占的列数。
+pub fn char_width(c: char) -> usize {
+    if c.is_control() || in_ranges(c, ZERO) {
+        0
+    } else if in_ranges(c, WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+/// 字符串在终端中占的列数，和字节数、字符数都不一样。
+///
+/// ```
+/// use rust_thinking::outline::display_width;
+///
+/// assert_eq!(display_width("hello"), 5);
+/// assert_eq!("你好".len(), 6);
+/// assert_eq!(display_width("你好"), 4);
+/// ```
+pub fn display_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+// 把一行按显示宽度切成若干段，宽字符放不下时整体移到下一段
+fn wrap(line: &str, max: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        let w = char_width(c);
+        if width + w > max && width > 0 {
+            pieces.push(std::mem::take(&mut current));
+            width = 0;
+        }
+        current.push(c);
+        width += w;
+    }
+    pieces.push(current);
+    pieces
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Border {
+    Ascii,
+    #[default]
+    Single,
+    Double,
+    Rounded,
+}
+
+struct Glyphs {
+    top_left: char,
+    top_right: char,
+    bottom_left: char,
+    bottom_right: char,
+    horizontal: char,
+    vertical: char,
+}
+
+impl Border {
+    fn glyphs(self) -> Glyphs {
+        let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = match self {
+            Border::Ascii => ['+', '+', '+', '+', '-', '|'],
+            Border::Single => ['┌', '┐', '└', '┘', '─', '│'],
+            Border::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+            Border::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+        };
+        Glyphs {
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+            horizontal,
+            vertical,
+        }
+    }
+}
+
+/// 文本框的样式：边框、内边距、标题和最大宽度。
+///
+/// ```
+/// use rust_thinking::outline::{Border, Outline};
+///
+/// let text = Outline::new()
+///     .border(Border::Rounded)
+///     .title("问候")
+///     .render(&"你好，世界\nhello");
+/// assert_eq!(
+///     text,
+///     "╭─ 问候 ─────╮\n\
+///      │ 你好，世界 │\n\
+///      │ hello      │\n\
+///      ╰────────────╯"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outline {
+    border: Border,
+    horizontal_padding: usize,
+    vertical_padding: usize,
+    title: Option<String>,
+    max_width: Option<usize>,
+}
+
+impl Default for Outline {
+    fn default() -> Outline {
+        Outline {
+            border: Border::default(),
+            horizontal_padding: 1,
+            vertical_padding: 0,
+            title: None,
+            max_width: None,
+        }
+    }
+}
+
+impl Outline {
+    pub fn new() -> Outline {
+        Outline::default()
+    }
+
+    pub fn border(mut self, border: Border) -> Outline {
+        self.border = border;
+        self
+    }
+
+    // 左右各留 horizontal 个空格，上下各留 vertical 个空行
+    pub fn padding(mut self, horizontal: usize, vertical: usize) -> Outline {
+        self.horizontal_padding = horizontal;
+        self.vertical_padding = vertical;
+        self
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Outline {
+        self.title = Some(title.into());
+        self
+    }
+
+    // 内容区超过 width 列的行会被折行，width 不包括边框和内边距
+    pub fn max_width(mut self, width: usize) -> Outline {
+        assert!(width > 0, "max width must be positive");
+        self.max_width = Some(width);
+        self
+    }
+
+    /// 渲染 `content` 的 `Display` 输出，返回的字符串不以换行结尾。
+    pub fn render<T: Display + ?Sized>(&self, content: &T) -> String {
+        // 制表符的宽度取决于终端，统一展开成 4 个空格
+        let text = content.to_string().replace('\t', "    ");
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        if let Some(max) = self.max_width {
+            lines = lines.iter().flat_map(|line| wrap(line, max)).collect();
+        }
+
+        let g = self.border.glyphs();
+        let pad = self.horizontal_padding;
+        let content_width = lines.iter().map(|l| display_width(l)).max().unwrap_or(0);
+        // 标题两侧各留一个空格，再各留至少一段横线
+        let title_width = self.title.as_deref().map_or(0, display_width);
+        let mut inner = content_width + 2 * pad;
+        if self.title.is_some() {
+            inner = inner.max(title_width + 4);
+        }
+        let horizontal = |n: usize| g.horizontal.to_string().repeat(n);
+
+        let mut out = Vec::with_capacity(lines.len() + 2 * self.vertical_padding + 2);
+        out.push(match &self.title {
+            Some(title) => format!(
+                "{}{} {} {}{}",
+                g.top_left,
+                g.horizontal,
+                title,
+                horizontal(inner - title_width - 3),
+                g.top_right
+            ),
+            None => format!("{}{}{}", g.top_left, horizontal(inner), g.top_right),
+        });
+        let blank = format!("{}{}{}", g.vertical, " ".repeat(inner), g.vertical);
+        out.extend(std::iter::repeat_n(blank.clone(), self.vertical_padding));
+        for line in &lines {
+            let fill = inner - pad - display_width(line);
+            out.push(format!(
+                "{}{}{}{}{}",
+                g.vertical,
+                " ".repeat(pad),
+                line,
+                " ".repeat(fill),
+                g.vertical
+            ));
+        }
+        out.extend(std::iter::repeat_n(blank, self.vertical_padding));
+        out.push(format!(
+            "{}{}{}",
+            g.bottom_left,
+            horizontal(inner),
+            g.bottom_right
+        ));
+        out.join("\n")
+    }
+}
+
+/// 把 `Display` 的输出画在文本框里，所有实现了 `Display` 的类型都自动实现了它。
+///
+/// ```
+/// use rust_thinking::outline::{Border, Outline, OutlinePrint};
+///
+/// assert_eq!(42.outline(), "┌────┐\n│ 42 │\n└────┘");
+/// assert_eq!(
+///     "ok".outline_with(&Outline::new().border(Border::Ascii).padding(0, 0)),
+///     "+--+\n|ok|\n+--+"
+/// );
+/// ```
+pub trait OutlinePrint: Display {
+    fn outline(&self) -> String {
+        Outline::default().render(self)
+    }
+
+    fn outline_with(&self, style: &Outline) -> String {
+        style.render(self)
+    }
+
+    fn outline_print(&self) {
+        println!("{}", self.outline());
+    }
+}
+
+impl<T: Display + ?Sized> OutlinePrint for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 每一行的显示宽度都相同，说明边框没有画歪
+    fn assert_aligned(text: &str) {
+        let widths: Vec<usize> = text.lines().map(display_width).collect();
+        assert!(
+            widths.windows(2).all(|w| w[0] == w[1]),
+            "misaligned box {:?}:\n{}",
+            widths,
+            text
+        );
+    }
+
+    #[test]
+    fn measures_display_width() {
+        assert_eq!(display_width(""), 0);
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("中文abc"), 7);
+        assert_eq!(display_width("ｆｕｌｌ"), 8);
+        assert_eq!(display_width("한글"), 4);
+        assert_eq!(display_width("🦀"), 2);
+        // e + 组合重音符
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("a\u{200B}b"), 2);
+    }
+
+    #[test]
+    fn renders_every_border_style() {
+        let cases = [
+            (Border::Ascii, "+----+\n| hi |\n+----+"),
+            (Border::Single, "┌────┐\n│ hi │\n└────┘"),
+            (Border::Double, "╔════╗\n║ hi ║\n╚════╝"),
+            (Border::Rounded, "╭────╮\n│ hi │\n╰────╯"),
+        ];
+        for (border, expected) in cases {
+            assert_eq!(Outline::new().border(border).render("hi"), expected);
+        }
+    }
+
+    #[test]
+    fn aligns_wide_and_multi_line_content() {
+        let text = "Rust 语言圣经\n所有权\n\nborrow checker 🦀";
+        let rendered = text.outline();
+        assert_aligned(&rendered);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[1], "│ Rust 语言圣经     │");
+        assert_eq!(lines[3], format!("│{}│", " ".repeat(19)));
+        assert_eq!(lines[4], "│ borrow checker 🦀 │");
+        // 字节数和字符数都不能用来画边框
+        assert_ne!(lines[1].len(), lines[4].len());
+        assert_ne!(lines[1].chars().count(), lines[4].chars().count());
+    }
+
+    #[test]
+    fn padding_and_title() {
+        let style = Outline::new()
+            .border(Border::Ascii)
+            .padding(2, 1)
+            .title("T");
+        assert_eq!(
+            style.render("x"),
+            "+- T -+\n|     |\n|  x  |\n|     |\n+-----+"
+        );
+
+        // 标题比内容宽时，边框跟着变宽
+        let rendered = Outline::new().title("标题很长").render("a");
+        assert_eq!(rendered, "┌─ 标题很长 ─┐\n│ a          │\n└────────────┘");
+        assert_aligned(&rendered);
+    }
+
+    #[test]
+    fn wraps_long_lines_by_width() {
+        let style = Outline::new().border(Border::Ascii).max_width(4);
+        assert_eq!(
+            style.render("abcdefghij"),
+            "+------+\n| abcd |\n| efgh |\n| ij   |\n+------+"
+        );
+        // 宽字符不会被拆开，放不下时移到下一行
+        let rendered = style.render("ab中文字");
+        assert_eq!(rendered, "+------+\n| ab中 |\n| 文字 |\n+------+");
+        assert_eq!(wrap("中文", 1), ["中", "文"]);
+    }
+
+    #[test]
+    fn handles_empty_tabs_and_unsized_values() {
+        assert_eq!("".outline(), "┌──┐\n│  │\n└──┘");
+        assert_eq!(
+            Outline::new().border(Border::Ascii).render("a\tb"),
+            "+--------+\n| a    b |\n+--------+"
+        );
+        let s: &str = "dyn";
+        let d: &dyn Display = &s;
+        assert_eq!(d.outline(), "┌─────┐\n│ dyn │\n└─────┘");
+        assert_aligned(&format!("{}\n{}", 1.5, "二").outline());
+    }
+}
@@ -0,0 +1,346 @@
+use std::{
+    any::Any,
+    cell::RefCell,
+    fmt,
+    panic::{self, AssertUnwindSafe, PanicHookInfo},
+    sync::{Arc, Mutex, Once, Weak},
+    thread,
+};
+
+// panic 工具
+// return_values_and_error_handling 章节的笔记里讲过 panic 的流程：先调用 panic hook，再进行栈展开，展开途中可以被 catch_unwind 拦截，
+// 拦截后还可以通过 resume_unwind 继续展开；子线程 panic 时只有这个线程会终止，panic 的信息通过 JoinHandle::join 收集。
+// 这里把这些步骤写成可以复用的工具：
+// 1. PanicLog：安装一个全局 panic hook，把任意线程上的 panic(线程名、位置、消息)记录到共享日志中
+// 2. run_isolated：在当前线程中运行闭包，panic 会被转换成 Err(PanicReport)
+// 3. spawn_worker：在子线程中运行闭包，通过 JoinHandle::join 观察子线程是否 panic
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PanicReport {
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+}
+
+impl PanicReport {
+    fn from_hook(info: &PanicHookInfo) -> PanicReport {
+        PanicReport {
+            thread: current_thread_name(),
+            message: payload_message(info.payload()),
+            location: info.location().map(|l| l.to_string()),
+        }
+    }
+
+    // panic hook 没有机会记录时(例如 resume_unwind 不会调用 hook)，只能从 payload 中还原出消息
+    fn from_payload(payload: &(dyn Any + Send)) -> PanicReport {
+        PanicReport {
+            thread: current_thread_name(),
+            message: payload_message(payload),
+            location: None,
+        }
+    }
+}
+
+impl fmt::Display for PanicReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "thread '{}' panicked at {}: {}",
+            self.thread,
+            self.location.as_deref().unwrap_or("<unknown>"),
+            self.message
+        )
+    }
+}
+
+fn current_thread_name() -> String {
+    thread::current().name().unwrap_or("<unnamed>").to_string()
+}
+
+// panic! 的 payload 通常是 &str 或 String，panic_any 可以传入任意类型，这时只能输出类型占位符
+pub fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else if let Some(report) = payload.downcast_ref::<PanicReport>() {
+        report.message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+type Records = Mutex<Vec<PanicReport>>;
+
+// 所有还活着的 PanicLog，日志被 drop 之后对应的 Weak 会在下一次 panic 时被清理掉
+static LOGS: Mutex<Vec<Weak<Records>>> = Mutex::new(Vec::new());
+
+static INSTALL_HOOK: Once = Once::new();
+
+thread_local! {
+    // 只有在 run_isolated 内部才会是 Some，hook 把报告写进去之后就不再打印默认的 panic 信息
+    static ISOLATED: RefCell<Option<Option<PanicReport>>> = const { RefCell::new(None) };
+}
+
+// hook 在整个进程中只安装一次，安装前已有的 hook(默认 hook 或者其它代码安装的 hook)依然会被调用
+fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let report = PanicReport::from_hook(info);
+
+            let mut logs = LOGS.lock().unwrap_or_else(|e| e.into_inner());
+            logs.retain(|log| match log.upgrade() {
+                Some(records) => {
+                    let mut records = records.lock().unwrap_or_else(|e| e.into_inner());
+                    records.push(report.clone());
+                    true
+                }
+                None => false,
+            });
+            drop(logs);
+
+            let isolated = ISOLATED
+                .try_with(|slot| match slot.borrow_mut().as_mut() {
+                    Some(captured) => {
+                        *captured = Some(report);
+                        true
+                    }
+                    None => false,
+                })
+                .unwrap_or(false);
+            if !isolated {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// 记录 panic 的共享日志，可以 clone 之后交给其它线程，所有线程上的 panic 都会被记录下来。
+///
+/// ```
+/// use std::thread;
+/// use rust_thinking::panic_tools::PanicLog;
+///
+/// let log = PanicLog::install();
+/// let worker = thread::Builder::new()
+///     .name("doc-worker".to_string())
+///     .spawn(|| panic!("worker failed"))
+///     .unwrap();
+/// assert!(worker.join().is_err());
+///
+/// let records = log.records();
+/// let record = records.iter().find(|r| r.thread == "doc-worker").unwrap();
+/// assert_eq!(record.message, "worker failed");
+/// ```
+#[derive(Clone, Default)]
+pub struct PanicLog {
+    records: Arc<Records>,
+}
+
+impl PanicLog {
+    // 安装 panic hook 并开始记录，日志的所有副本都被 drop 之后自动停止记录
+    pub fn install() -> PanicLog {
+        install_hook();
+        let log = PanicLog::default();
+        LOGS.lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::downgrade(&log.records));
+        log
+    }
+
+    pub fn records(&self) -> Vec<PanicReport> {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    // 取出目前为止的所有记录并清空日志
+    pub fn take(&self) -> Vec<PanicReport> {
+        std::mem::take(&mut *self.records.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// 在当前线程中运行 `f`，panic 会被转换成 `Err(PanicReport)`，并且不会打印默认的 panic 信息。
+///
+/// 和 `catch_unwind` 一样，调用方需要保证 panic 之后不会观察到被破坏的状态(这里使用了 `AssertUnwindSafe`)。
+///
+/// ```
+/// use rust_thinking::panic_tools::run_isolated;
+///
+/// assert_eq!(run_isolated(|| 1 + 1), Ok(2));
+///
+/// let report = run_isolated(|| -> i32 { panic!("boom") }).unwrap_err();
+/// assert_eq!(report.message, "boom");
+/// assert!(report.location.is_some());
+/// ```
+pub fn run_isolated<T>(f: impl FnOnce() -> T) -> Result<T, PanicReport> {
+    install_hook();
+    // 保存外层的状态，run_isolated 可以嵌套使用
+    let outer = ISOLATED.with(|slot| slot.replace(Some(None)));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    let captured = ISOLATED.with(|slot| slot.replace(outer)).flatten();
+
+    result.map_err(|payload| match payload.downcast::<PanicReport>() {
+        Ok(report) => *report,
+        Err(payload) => captured.unwrap_or_else(|| PanicReport::from_payload(payload.as_ref())),
+    })
+}
+
+/// 在名为 `name` 的子线程中运行 `f`，并通过 `JoinHandle::join` 观察它是否 panic。
+///
+/// 子线程内部先用 `run_isolated` 拿到完整的报告，再通过 `resume_unwind` 把报告作为 payload 继续展开，
+/// 这样 `join` 返回的 `Err` 中就带有 panic 的位置。
+///
+/// ```
+/// use rust_thinking::panic_tools::spawn_worker;
+///
+/// assert_eq!(spawn_worker("adder", || 40 + 2), Ok(42));
+///
+/// let report = spawn_worker("divider", || -> i32 { panic!("division by zero") }).unwrap_err();
+/// assert_eq!(report.thread, "divider");
+/// assert_eq!(report.message, "division by zero");
+/// ```
+pub fn spawn_worker<T: Send + 'static>(
+    name: &str,
+    f: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, PanicReport> {
+    let handle = thread::Builder::new()
+        .name(name.to_string())
+        .spawn(|| match run_isolated(f) {
+            Ok(value) => value,
+            Err(report) => panic::resume_unwind(Box::new(report)),
+        })
+        .expect("failed to spawn worker thread");
+
+    handle
+        .join()
+        .map_err(|payload| match payload.downcast::<PanicReport>() {
+            Ok(report) => *report,
+            Err(payload) => PanicReport {
+                thread: name.to_string(),
+                message: payload_message(payload.as_ref()),
+                location: None,
+            },
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_isolated_converts_panics() {
+        assert_eq!(run_isolated(|| "ok"), Ok("ok"));
+
+        let report = run_isolated(|| -> () { panic!("failed with {}", 42) }).unwrap_err();
+        assert_eq!(report.message, "failed with 42");
+        assert!(report
+            .location
+            .as_deref()
+            .unwrap()
+            .contains("panic_tools.rs"));
+        assert_eq!(report.thread, current_thread_name());
+        assert!(report
+            .to_string()
+            .contains("panicked at src/panic_tools.rs:"));
+
+        let report = run_isolated(|| {
+            let v: Vec<i32> = Vec::new();
+            v[3]
+        })
+        .unwrap_err();
+        assert!(report.message.contains("index out of bounds"));
+
+        let report = run_isolated(|| panic::panic_any(7u8)).unwrap_err();
+        assert_eq!(report.message, "Box<dyn Any>");
+    }
+
+    #[test]
+    fn run_isolated_nests() {
+        let outer = run_isolated(|| {
+            let inner = run_isolated(|| -> () { panic!("inner") }).unwrap_err();
+            assert_eq!(inner.message, "inner");
+            panic!("outer");
+        })
+        .unwrap_err();
+        assert_eq!(outer.message, "outer");
+        assert!(outer.location.is_some());
+    }
+
+    #[test]
+    fn shared_log_records_every_thread() {
+        let log = PanicLog::install();
+        let handles: Vec<_> = (0..3)
+            .map(|i| {
+                thread::Builder::new()
+                    .name(format!("log-worker-{}", i))
+                    .spawn(move || {
+                        if i != 1 {
+                            panic!("worker {} failed", i);
+                        }
+                    })
+                    .unwrap()
+            })
+            .collect();
+        let joined: Vec<bool> = handles.into_iter().map(|h| h.join().is_ok()).collect();
+        assert_eq!(joined, vec![false, true, false]);
+
+        // 其它测试也可能同时 panic，只看本测试创建的线程
+        let mut mine: Vec<PanicReport> = log
+            .take()
+            .into_iter()
+            .filter(|r| r.thread.starts_with("log-worker-"))
+            .collect();
+        mine.sort_by(|a, b| a.thread.cmp(&b.thread));
+        assert_eq!(mine.len(), 2);
+        assert_eq!(mine[0].thread, "log-worker-0");
+        assert_eq!(mine[0].message, "worker 0 failed");
+        assert_eq!(mine[1].thread, "log-worker-2");
+        assert!(mine[1]
+            .location
+            .as_deref()
+            .unwrap()
+            .contains("panic_tools.rs"));
+
+        // 被隔离的 panic 同样会记录到日志中
+        let cloned = log.clone();
+        let name = current_thread_name();
+        let _ = run_isolated(|| -> () { panic!("isolated") });
+        assert!(cloned
+            .records()
+            .iter()
+            .any(|r| r.thread == name && r.message == "isolated"));
+
+        // 日志被 drop 之后不再记录
+        let weak = Arc::downgrade(&log.records);
+        drop(log);
+        drop(cloned);
+        assert!(weak.upgrade().is_none());
+        let _ = run_isolated(|| -> () { panic!("after drop") });
+    }
+
+    #[test]
+    fn worker_panics_are_observed_through_join() {
+        let report = spawn_worker("join-worker", || -> u32 {
+            let items: Vec<u32> = Vec::new();
+            items.iter().copied().max().expect("no items")
+        })
+        .unwrap_err();
+        assert_eq!(report.thread, "join-worker");
+        assert_eq!(report.message, "no items");
+        assert!(report
+            .location
+            .as_deref()
+            .unwrap()
+            .contains("panic_tools.rs"));
+
+        assert_eq!(spawn_worker("ok-worker", || vec![1, 2, 3].len()), Ok(3));
+
+        // 不经过 spawn_worker，直接用 thread::spawn + join 观察 payload
+        let handle = thread::spawn(|| panic!("{}", String::from("plain thread")));
+        let payload = handle.join().unwrap_err();
+        assert_eq!(payload_message(payload.as_ref()), "plain thread");
+    }
+}
@@ -1,3 +1,5 @@
+use crate::geometry::Point;
+
 pub fn pattern_matching_3() {
     // 匹配字面值
     let x = 1;
@@ -53,10 +55,7 @@ pub fn pattern_matching_3() {
 
     // 解构并分解值
     // 解构结构体
-    struct Point {
-        x: i32,
-        y: i32,
-    }
+    // 这里使用 geometry 模块中的 Point，它的 x、y 都是公有字段，和自己定义的结构体一样可以构造、解构
 
     let p = Point { x: 0, y: 7 };
 
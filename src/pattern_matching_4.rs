@@ -1,8 +1,5 @@
-#[derive(Debug)]
-struct Point {
-    x: i32,
-    y: i32,
-}
+// 这里直接使用 geometry 模块中的 Point，它的 x、y 都是公有字段，可以像普通结构体一样解构
+use crate::geometry::Point;
 
 pub fn pattern_matching_4() {
     // 匹配守卫提供的额外条件
@@ -0,0 +1,374 @@
+use std::{cmp::Ordering, collections::BTreeMap, iter::Sum};
+
+use crate::learn_iterator::Shoe;
+
+// 集合上的查询构建器
+// learn_iterator::shoes_in_size 只能按尺码过滤 Vec<Shoe>，每换一个条件就要再写一个函数。
+// Query 把过滤、排序、分组、截断和投影记录成一份查询计划，计划本身不持有数据，调用 run 时才作用在任意 IntoIterator 上：
+// 1. 只有过滤和截断时完全是惰性的，结果迭代器每次 next 才从数据源中取值，limit 取够之后不会再读取数据源
+// 2. 排序和分组需要看到全部数据，run 时会先收集过滤后的元素，再惰性地产出结果
+// 3. 分组后的每一组是一个 Group，提供 count、sum、min、max、avg 聚合函数
+
+type Predicate<'q, T> = Box<dyn Fn(&T) -> bool + 'q>;
+type Comparator<'q, T> = Box<dyn Fn(&T, &T) -> Ordering + 'q>;
+
+/// 查询计划，`T` 是数据源中元素的类型。
+///
+/// ```
+/// use rust_thinking::{learn_iterator::Shoe, query::{shoe_catalog, Query}};
+///
+/// // 等价于 shoes_in_size(shoes, 10)，再按款式排序取前两个
+/// let styles: Vec<String> = Query::<Shoe>::new()
+///     .filter(|s| s.size == 10)
+///     .order_by(|s| s.style.clone())
+///     .limit(2)
+///     .select(|s| s.style)
+///     .run(shoe_catalog())
+///     .collect();
+/// assert_eq!(styles, ["boot", "loafer"]);
+/// ```
+pub struct Query<'q, T> {
+    filters: Vec<Predicate<'q, T>>,
+    orderings: Vec<Comparator<'q, T>>,
+    limit: Option<usize>,
+}
+
+impl<'q, T: 'q> Default for Query<'q, T> {
+    fn default() -> Query<'q, T> {
+        Query::new()
+    }
+}
+
+impl<'q, T: 'q> Query<'q, T> {
+    pub fn new() -> Query<'q, T> {
+        Query {
+            filters: Vec::new(),
+            orderings: Vec::new(),
+            limit: None,
+        }
+    }
+
+    // 多个 filter 之间是"并且"的关系
+    pub fn filter(mut self, predicate: impl Fn(&T) -> bool + 'q) -> Query<'q, T> {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    // 按 key 升序排列，多次调用时后面的 key 只在前面的 key 相等时起作用；排序是稳定的
+    pub fn order_by<K: Ord>(mut self, key: impl Fn(&T) -> K + 'q) -> Query<'q, T> {
+        self.orderings
+            .push(Box::new(move |a, b| key(a).cmp(&key(b))));
+        self
+    }
+
+    pub fn order_by_desc<K: Ord>(mut self, key: impl Fn(&T) -> K + 'q) -> Query<'q, T> {
+        self.orderings
+            .push(Box::new(move |a, b| key(b).cmp(&key(a))));
+        self
+    }
+
+    pub fn limit(mut self, n: usize) -> Query<'q, T> {
+        self.limit = Some(n);
+        self
+    }
+
+    /// 按 `key` 分组，之后的 `limit`、`select` 作用在分组上。
+    pub fn group_by<K: Ord>(self, key: impl Fn(&T) -> K + 'q) -> Grouped<'q, T, K> {
+        Grouped {
+            query: self,
+            key: Box::new(key),
+            limit: None,
+        }
+    }
+
+    /// 把每个元素映射成 `R`。
+    pub fn select<R>(self, f: impl Fn(T) -> R + 'q) -> Select<'q, T, R> {
+        Select {
+            query: self,
+            f: Box::new(f),
+        }
+    }
+
+    /// 在整个结果集上做聚合，相当于只有一组的 `group_by`。
+    pub fn aggregate(&self, source: impl IntoIterator<Item = T>) -> Group<(), T> {
+        Group {
+            key: (),
+            items: self.run(source).collect(),
+        }
+    }
+
+    pub fn run<'a, S>(&'a self, source: S) -> Box<dyn Iterator<Item = T> + 'a>
+    where
+        S: IntoIterator<Item = T>,
+        S::IntoIter: 'a,
+    {
+        let filtered = source
+            .into_iter()
+            .filter(move |item| self.filters.iter().all(|p| p(item)));
+        let limit = self.limit.unwrap_or(usize::MAX);
+        if self.orderings.is_empty() {
+            return Box::new(filtered.take(limit));
+        }
+        let mut items: Vec<T> = filtered.collect();
+        items.sort_by(|a, b| {
+            self.orderings
+                .iter()
+                .map(|cmp| cmp(a, b))
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        Box::new(items.into_iter().take(limit))
+    }
+}
+
+/// 带投影的查询，由 [`Query::select`] 创建。
+pub struct Select<'q, T, R> {
+    query: Query<'q, T>,
+    f: Box<dyn Fn(T) -> R + 'q>,
+}
+
+impl<'q, T: 'q, R> Select<'q, T, R> {
+    pub fn run<'a, S>(&'a self, source: S) -> Box<dyn Iterator<Item = R> + 'a>
+    where
+        S: IntoIterator<Item = T>,
+        S::IntoIter: 'a,
+    {
+        Box::new(self.query.run(source).map(&self.f))
+    }
+}
+
+/// 分组查询，由 [`Query::group_by`] 创建，分组按键的升序产出。
+pub struct Grouped<'q, T, K> {
+    query: Query<'q, T>,
+    key: Box<dyn Fn(&T) -> K + 'q>,
+    limit: Option<usize>,
+}
+
+impl<'q, T: 'q, K: Ord + 'q> Grouped<'q, T, K> {
+    // 最多产出 n 个分组
+    pub fn limit(mut self, n: usize) -> Grouped<'q, T, K> {
+        self.limit = Some(n);
+        self
+    }
+
+    pub fn select<R>(self, f: impl Fn(Group<K, T>) -> R + 'q) -> GroupedSelect<'q, T, K, R> {
+        GroupedSelect {
+            grouped: self,
+            f: Box::new(f),
+        }
+    }
+
+    // 组内元素保持查询的排序
+    pub fn run<'a, S>(&'a self, source: S) -> Box<dyn Iterator<Item = Group<K, T>> + 'a>
+    where
+        S: IntoIterator<Item = T>,
+        S::IntoIter: 'a,
+    {
+        let mut groups: BTreeMap<K, Vec<T>> = BTreeMap::new();
+        for item in self.query.run(source) {
+            groups.entry((self.key)(&item)).or_default().push(item);
+        }
+        Box::new(
+            groups
+                .into_iter()
+                .map(|(key, items)| Group { key, items })
+                .take(self.limit.unwrap_or(usize::MAX)),
+        )
+    }
+}
+
+/// 对分组的投影，由 [`Grouped::select`] 创建。
+pub struct GroupedSelect<'q, T, K, R> {
+    grouped: Grouped<'q, T, K>,
+    f: Box<dyn Fn(Group<K, T>) -> R + 'q>,
+}
+
+impl<'q, T: 'q, K: Ord + 'q, R> GroupedSelect<'q, T, K, R> {
+    pub fn run<'a, S>(&'a self, source: S) -> Box<dyn Iterator<Item = R> + 'a>
+    where
+        S: IntoIterator<Item = T>,
+        S::IntoIter: 'a,
+    {
+        Box::new(self.grouped.run(source).map(&self.f))
+    }
+}
+
+/// 一个分组：分组的键和组内的所有元素。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group<K, T> {
+    pub key: K,
+    pub items: Vec<T>,
+}
+
+impl<K, T> Group<K, T> {
+    pub fn count(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn sum<N: Sum>(&self, f: impl Fn(&T) -> N) -> N {
+        self.items.iter().map(f).sum()
+    }
+
+    pub fn min<N: Ord>(&self, f: impl Fn(&T) -> N) -> Option<N> {
+        self.items.iter().map(f).min()
+    }
+
+    pub fn max<N: Ord>(&self, f: impl Fn(&T) -> N) -> Option<N> {
+        self.items.iter().map(f).max()
+    }
+
+    // 空分组没有平均值
+    pub fn avg<N: Into<f64>>(&self, f: impl Fn(&T) -> N) -> Option<f64> {
+        if self.items.is_empty() {
+            return None;
+        }
+        let total: f64 = self.items.iter().map(|item| f(item).into()).sum();
+        Some(total / self.items.len() as f64)
+    }
+}
+
+/// 示例数据：一个鞋店的库存。
+pub fn shoe_catalog() -> Vec<Shoe> {
+    [
+        (10, "sneaker"),
+        (13, "sandal"),
+        (10, "boot"),
+        (8, "sneaker"),
+        (11, "boot"),
+        (10, "loafer"),
+        (9, "sandal"),
+        (12, "sneaker"),
+        (10, "sneaker"),
+        (7, "slipper"),
+    ]
+    .into_iter()
+    .map(|(size, style)| Shoe {
+        size,
+        style: style.to_string(),
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learn_iterator::shoes_in_size;
+    use std::cell::Cell;
+
+    #[test]
+    fn filter_generalizes_shoes_in_size() {
+        let query = Query::new().filter(|s: &Shoe| s.size == 10);
+        let shoes: Vec<Shoe> = query.run(shoe_catalog()).collect();
+        assert_eq!(shoes, shoes_in_size(shoe_catalog(), 10));
+
+        // 查询计划可以重复使用，数据源可以是任意 IntoIterator
+        let catalog = shoe_catalog();
+        let small = Query::<&Shoe>::new()
+            .filter(|s| s.size < 10)
+            .filter(|s| s.style != "slipper");
+        let sizes: Vec<u32> = small.run(&catalog).map(|s| s.size).collect();
+        assert_eq!(sizes, [8, 9]);
+        assert_eq!(small.run(catalog.iter().rev()).count(), 2);
+    }
+
+    #[test]
+    fn order_by_is_stable_and_chains() {
+        let sizes: Vec<(u32, String)> = Query::<Shoe>::new()
+            .order_by(|s| s.style.clone())
+            .order_by_desc(|s| s.size)
+            .select(|s| (s.size, s.style))
+            .run(shoe_catalog())
+            .collect();
+        let mut expected: Vec<(u32, String)> = shoe_catalog()
+            .into_iter()
+            .map(|s| (s.size, s.style))
+            .collect();
+        expected.sort_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)));
+        assert_eq!(sizes, expected);
+        assert_eq!(sizes[0], (11, "boot".to_string()));
+    }
+
+    #[test]
+    fn unordered_queries_are_lazy() {
+        let pulled = Cell::new(0);
+        let source = shoe_catalog()
+            .into_iter()
+            .inspect(|_| pulled.set(pulled.get() + 1));
+        let query = Query::<Shoe>::new().filter(|s| s.size == 10).limit(2);
+        let mut results = query.run(source);
+        assert_eq!(pulled.get(), 0);
+        assert_eq!(results.next().unwrap().style, "sneaker");
+        assert_eq!(pulled.get(), 1);
+        assert_eq!(results.next().unwrap().style, "boot");
+        assert!(results.next().is_none());
+        // 取够两个之后不再读取数据源
+        assert_eq!(pulled.get(), 3);
+    }
+
+    #[test]
+    fn group_by_with_aggregates() {
+        let report: Vec<(String, usize, u32, u32, u32, f64)> = Query::<Shoe>::new()
+            .filter(|s| s.style != "slipper")
+            .group_by(|s| s.style.clone())
+            .select(|g| {
+                (
+                    g.key.clone(),
+                    g.count(),
+                    g.sum(|s| s.size),
+                    g.min(|s| s.size).unwrap(),
+                    g.max(|s| s.size).unwrap(),
+                    g.avg(|s| s.size).unwrap(),
+                )
+            })
+            .run(shoe_catalog())
+            .collect();
+        assert_eq!(
+            report,
+            [
+                ("boot".to_string(), 2, 21, 10, 11, 10.5),
+                ("loafer".to_string(), 1, 10, 10, 10, 10.0),
+                ("sandal".to_string(), 2, 22, 9, 13, 11.0),
+                ("sneaker".to_string(), 4, 40, 8, 12, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn limits_before_and_after_grouping() {
+        let query = Query::<Shoe>::new()
+            .order_by(|s| s.size)
+            .limit(4)
+            .group_by(|s| s.style.clone())
+            .limit(2);
+        let groups: Vec<Group<String, Shoe>> = query.run(shoe_catalog()).collect();
+        // 前 4 双最小的鞋是 7 slipper、8 sneaker、9 sandal、10 sneaker，分组后取前两组
+        let keys: Vec<&str> = groups.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(keys, ["sandal", "slipper"]);
+        assert_eq!(groups[0].items[0].size, 9);
+
+        let sneakers = Query::<Shoe>::new()
+            .order_by(|s| s.size)
+            .limit(4)
+            .group_by(|s| s.style.clone())
+            .run(shoe_catalog())
+            .find(|g| g.key == "sneaker")
+            .unwrap();
+        let sizes: Vec<u32> = sneakers.items.iter().map(|s| s.size).collect();
+        assert_eq!(sizes, [8, 10]);
+    }
+
+    #[test]
+    fn aggregate_over_whole_result() {
+        let all = Query::<Shoe>::new().aggregate(shoe_catalog());
+        assert_eq!(all.count(), 10);
+        assert_eq!(all.max(|s| s.style.clone()).as_deref(), Some("sneaker"));
+
+        let none = Query::<Shoe>::new()
+            .filter(|s| s.size > 40)
+            .aggregate(shoe_catalog());
+        assert_eq!(none.count(), 0);
+        assert_eq!(none.sum(|s| s.size), 0);
+        assert_eq!(none.min(|s| s.size), None);
+        assert_eq!(none.avg(|s| s.size), None);
+    }
+}
@@ -2,6 +2,9 @@ use std::{
     cell::{Cell, RefCell},
     rc::Rc,
 };
+
+use crate::broker::{Broker, Channel, LocalBroker, Overflow};
+
 pub fn cell_refcell() {
     // Cell 和 RefCell
     // Rust 的编译器之严格，可以说是举世无双。特别是在所有权方面，Rust 通过严格的规则来保证所有权和借用的正确性，最终为程序的安全保驾护航。
@@ -135,22 +138,7 @@ pub fn cell_refcell() {
     // 而当值可变时，最多只会有一个可变引用指向它，将其修改为不可变，那么最终依然是只有一个不可变的引用指向它。
     // 虽然基本借用规则是 Rust 的基石，然而在某些场景中，一个值可以在其方法内部被修改，同时对于其它代码不可变，是很有用的：
 
-    // 定义在外部库中的特征
-    pub trait Messenger {
-        fn send(&self, msg: String);
-    }
-
-    pub struct MsgQueue {
-        msg_cache: RefCell<Vec<String>>,
-    }
-
-    impl Messenger for MsgQueue {
-        fn send(&self, msg: String) {
-            println!("cache add: {}", msg);
-            self.msg_cache.borrow_mut().push(msg)
-        }
-    }
-
+    // Messenger 特征和 MsgQueue 定义在模块顶层：
     let mq = MsgQueue {
         msg_cache: RefCell::new(Vec::new()),
     };
@@ -158,6 +146,24 @@ pub fn cell_refcell() {
     mq.send("hello, world".to_string());
 
     // 这个 MQ 功能很弱，但是并不妨碍我们演示内部可变性的核心用法：通过包裹一层 RefCell，成功的让 &self 中的 msg_cache 成为一个可变值，然后实现对其的修改。
+    // broker 模块把它扩展成了按主题分发的消息代理，状态放在 Rc<RefCell<_>> 里，所有方法同样只需要 &self：
+    let broker = LocalBroker::new(2, Overflow::DropOldest);
+    let received = Rc::new(Cell::new(0));
+    let counter = received.clone();
+    broker.subscribe(
+        "greeting",
+        Box::new(move |_: &String| counter.set(counter.get() + 1)),
+    );
+    let greeting = Channel::new(&broker, "greeting");
+    for msg in ["hello", "hi", "hey"] {
+        greeting.send(msg.to_string());
+    }
+    println!(
+        "received {}, queued {:?}",
+        received.get(),
+        broker.drain("greeting")
+    );
+    // 它的线程安全版本 SyncBroker 把 Rc<RefCell<_>> 换成了 Arc<Mutex<_>>，两者实现了同一个 Broker 特征
 
     // Rc + RefCell 组合使用
     // 在 Rust 中，一个常见的组合就是 Rc 和 RefCell 在一起使用，前者可以实现一个数据拥有多个所有者，后者可以实现数据的可变性：
@@ -193,6 +199,7 @@ pub fn cell_refcell() {
     }
 
     // 从上面可以看出，从对内存的影响来看，仅仅多分配了三个usize/isize，并没有其它额外的负担。
+    // 运行 `rust_thinking layout` 可以看到实测的数字：Rc<RefCell<u64>> 的堆分配中，除了 u64 本身，多出来的就是这三个 usize 大小的计数(64 位平台上是 8 + 24 = 32 字节)。
 
     // CPU 损耗
     // 从 CPU 来看，损耗如下：
@@ -216,21 +223,10 @@ pub fn cell_refcell() {
     // 在 Rust 1.37 版本中新增了两个非常实用的方法：
     // 1. Cell::from_mut，该方法将 &mut T 转为 &Cell<T>
     // 2. Cell::as_slice_of_cells，该方法将 &Cell<[T]> 转为 &[Cell<T>]
-    fn is_even(i: i32) -> bool {
-        i % 2 == 0
-    }
-
-    fn retain_even(nums: &mut Vec<i32>) {
-        let slice: &[Cell<i32>] = Cell::from_mut(&mut nums[..]).as_slice_of_cells();
-
-        let mut i = 0;
-        for num in slice.iter().filter(|num| is_even(num.get())) {
-            slice[i].set(num.get());
-            i += 1;
-        }
-
-        nums.truncate(i);
-    }
+    // is_even 和 retain_even 定义在模块顶层
+    let mut nums = vec![1, 2, 3, 4, 5, 6];
+    retain_even(&mut nums);
+    assert_eq!(nums, vec![2, 4, 6]);
 
     // 此时代码将不会报错，因为 Cell 上的 set 方法获取的是不可变引用 pub fn set(&self, val: T)。
     // 当然，以上代码的本质还是对 Cell 的运用，只不过这两个方法可以很方便的帮我们把 &mut [T] 类型转换成 &[Cell<T>] 类型。
@@ -241,3 +237,93 @@ pub fn cell_refcell() {
     // 3. 从性能上看，RefCell 由于是非线程安全的，因此无需保证原子性，性能虽然有一点损耗，但是依然非常好，而 Cell 则完全不存在任何额外的性能损耗。
     // 4. Rc 跟 RefCell 结合使用可以实现多个所有者共享同一份数据，非常好用，但是潜在的性能损耗也要考虑进去，建议对于热点代码使用时，做好 benchmark。
 }
+
+/// 定义在外部库中的特征：发送消息，注意 `send` 只拿到了 `&self`。
+pub trait Messenger {
+    fn send(&self, msg: String);
+}
+
+/// 消息队列：通过 `RefCell` 让 `&self` 中的 `msg_cache` 成为一个可变值。
+///
+/// ```
+/// use rust_thinking::refcell::{Messenger, MsgQueue};
+///
+/// let mq = MsgQueue::new();
+/// mq.send("hello, world".to_string());
+/// assert_eq!(*mq.msg_cache.borrow(), vec!["hello, world".to_string()]);
+/// ```
+#[derive(Default)]
+pub struct MsgQueue {
+    pub msg_cache: RefCell<Vec<String>>,
+}
+
+impl MsgQueue {
+    pub fn new() -> MsgQueue {
+        MsgQueue {
+            msg_cache: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl Messenger for MsgQueue {
+    fn send(&self, msg: String) {
+        println!("cache add: {}", msg);
+        self.msg_cache.borrow_mut().push(msg)
+    }
+}
+
+/// 额度追踪器：用量越过额度的 75%、90%、100% 时通过注入的 `Messenger` 发出提醒。
+///
+/// 它只依赖 `Messenger` 特征，测试时可以换成 `mock` 模块中基于 `Recorder` 的替身。
+pub struct LimitTracker<'a, M: Messenger> {
+    messenger: &'a M,
+    value: usize,
+    max: usize,
+}
+
+impl<'a, M: Messenger> LimitTracker<'a, M> {
+    pub fn new(messenger: &'a M, max: usize) -> LimitTracker<'a, M> {
+        LimitTracker {
+            messenger,
+            value: 0,
+            max,
+        }
+    }
+
+    pub fn set_value(&mut self, value: usize) {
+        self.value = value;
+        let percentage = self.value as f64 / self.max as f64;
+        if percentage >= 1.0 {
+            self.messenger.send("错误：额度已经用完".to_string());
+        } else if percentage >= 0.9 {
+            self.messenger.send("紧急：额度已使用 90% 以上".to_string());
+        } else if percentage >= 0.75 {
+            self.messenger.send("警告：额度已使用 75% 以上".to_string());
+        }
+    }
+}
+
+pub fn is_even(i: i32) -> bool {
+    i % 2 == 0
+}
+
+/// 借助 `Cell::from_mut` 和 `as_slice_of_cells`，在遍历数组的同时原地保留偶数。
+///
+/// ```
+/// use rust_thinking::refcell::retain_even;
+///
+/// let mut nums = vec![1, 2, 3, 4, 5, 6];
+/// retain_even(&mut nums);
+/// assert_eq!(nums, vec![2, 4, 6]);
+/// ```
+pub fn retain_even(nums: &mut Vec<i32>) {
+    let slice: &[Cell<i32>] = Cell::from_mut(&mut nums[..]).as_slice_of_cells();
+
+    let mut i = 0;
+    for num in slice.iter().filter(|num| is_even(num.get())) {
+        slice[i].set(num.get());
+        i += 1;
+    }
+
+    nums.truncate(i);
+}
@@ -0,0 +1,289 @@
+use crate::*;
+
+// 章节注册表
+// 每个章节都登记一个名字(命令行中使用)、标题、标签以及入口函数，运行器通过这张表来决定运行哪些章节，
+// 表中的顺序就是学习的顺序，`run --all` 也会按照这个顺序执行。
+pub struct Chapter {
+    pub name: &'static str,
+    pub title: &'static str,
+    pub tags: &'static [&'static str],
+    pub entry: fn(),
+}
+
+impl Chapter {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag)
+    }
+}
+
+macro_rules! chapter {
+    ($name:ident, $title:expr, [$($tag:expr),* $(,)?], $entry:path) => {
+        Chapter {
+            name: stringify!($name),
+            title: $title,
+            tags: &[$($tag),*],
+            entry: $entry,
+        }
+    };
+}
+
+pub static CHAPTERS: &[Chapter] = &[
+    chapter!(
+        destructuring_assignment,
+        "destructuring assignment",
+        ["basics"],
+        destructuring_assignment::destructuring_assignment
+    ),
+    chapter!(
+        basic_types,
+        "basic types",
+        ["basics"],
+        basic_types::basic_types
+    ),
+    chapter!(
+        char_bool_unit,
+        "char bool unit",
+        ["basics"],
+        char_bool_unit::char_bool_unit
+    ),
+    chapter!(
+        statements_expressions,
+        "statements and expressions",
+        ["basics"],
+        statements_expressions::statements_expressions
+    ),
+    chapter!(
+        reference,
+        "references",
+        ["basics", "ownership"],
+        reference::reference
+    ),
+    chapter!(
+        complex_types,
+        "complex types",
+        ["basics"],
+        complex_types::complex_types
+    ),
+    chapter!(
+        learn_string,
+        "string",
+        ["basics", "collections"],
+        learn_string::learning_string
+    ),
+    chapter!(tuple, "tuple", ["basics"], tuple::tuple),
+    chapter!(
+        learn_struct,
+        "struct",
+        ["basics"],
+        learn_struct::learn_struct
+    ),
+    chapter!(
+        process_control,
+        "process control",
+        ["basics"],
+        process_control::process_control
+    ),
+    chapter!(
+        option,
+        "options",
+        ["basics", "pattern-matching"],
+        option::option
+    ),
+    chapter!(
+        pattern_matching_1,
+        "pattern matching",
+        ["pattern-matching"],
+        pattern_matching_1::pattern_matching
+    ),
+    chapter!(
+        pattern_matching_2,
+        "pattern matching 2",
+        ["pattern-matching"],
+        pattern_matching_2::pattern_matching_2
+    ),
+    chapter!(
+        pattern_matching_3,
+        "pattern matching 3",
+        ["pattern-matching"],
+        pattern_matching_3::pattern_matching_3
+    ),
+    chapter!(
+        pattern_matching_4,
+        "pattern matching 4",
+        ["pattern-matching"],
+        pattern_matching_4::pattern_matching_4
+    ),
+    chapter!(
+        function,
+        "function",
+        ["basics", "functional"],
+        function::function
+    ),
+    chapter!(method, "method", ["basics"], method::method),
+    chapter!(generics, "generics", ["generics"], generics::generics),
+    chapter!(
+        learn_trait,
+        "trait",
+        ["traits", "generics"],
+        learn_trait::learn_trait
+    ),
+    chapter!(trait_obj, "trait object", ["traits"], trait_obj::trait_obj),
+    chapter!(deep_trait, "deep trait", ["traits"], deep_trait::deep_trait),
+    chapter!(learn_vec, "vec", ["collections"], learn_vec::learn_vec),
+    chapter!(hash_map, "hashmap", ["collections"], hash_map::hash_map),
+    chapter!(
+        type_conversion,
+        "type conversion",
+        ["basics"],
+        type_conversion::type_conversion
+    ),
+    chapter!(
+        format_output,
+        "format output",
+        ["basics", "traits"],
+        format_output::format_output
+    ),
+    chapter!(
+        return_values_and_error_handling,
+        "return values and error handling",
+        ["error-handling"],
+        return_values_and_error_handling::return_values_and_error_handling
+    ),
+    chapter!(
+        notes_and_documentation,
+        "notes and documentation",
+        ["basics"],
+        notes_and_documentation::notes_and_documentation
+    ),
+    chapter!(lifecycle, "life cycle", ["lifetimes"], lifecycle::lifecycle),
+    chapter!(
+        deep_lifecycle,
+        "deep life cycle",
+        ["lifetimes"],
+        deep_lifecycle::deep_lifecycle
+    ),
+    chapter!(
+        lifecycle_exp_1,
+        "deep life cycle example 1",
+        ["lifetimes"],
+        lifecycle_exp_1::lifecycle_exp_1
+    ),
+    chapter!(
+        lifecycle_exp_2,
+        "deep life cycle example 2",
+        ["lifetimes"],
+        lifecycle_exp_2::lifecycle_exp_2
+    ),
+    chapter!(
+        static_lifecycle,
+        "static life cycle",
+        ["lifetimes"],
+        static_lifecycle::static_lifecycle
+    ),
+    chapter!(closure, "closure", ["functional"], closure::closure),
+    chapter!(
+        learn_iterator,
+        "iterator",
+        ["functional", "collections"],
+        learn_iterator::iterator
+    ),
+    chapter!(
+        newtype_and_type_aliases,
+        "newtype and type aliases",
+        ["traits", "advanced"],
+        newtype_and_type_aliases::newtype_and_type_aliases
+    ),
+    chapter!(
+        sized_dst,
+        "sized and dst",
+        ["advanced"],
+        sized_dst::sized_dst
+    ),
+    chapter!(
+        enumerations_and_integers,
+        "enumerations and integers",
+        ["basics"],
+        enumerations_and_integers::enumerations_and_integers
+    ),
+    chapter!(learn_box, "box", ["smart-pointers"], learn_box::learn_box),
+    chapter!(
+        learn_deref,
+        "deref",
+        ["smart-pointers", "traits"],
+        learn_deref::learn_deref
+    ),
+    chapter!(
+        learn_drop,
+        "drop",
+        ["smart-pointers", "traits"],
+        learn_drop::learn_drop
+    ),
+    chapter!(rc_arc, "rc arc", ["smart-pointers"], rc_arc::Rc_Arc),
+    chapter!(
+        refcell,
+        "cell and refcell",
+        ["smart-pointers"],
+        refcell::cell_refcell
+    ),
+    chapter!(
+        circular_reference_and_self_reference,
+        "circular reference and self-reference",
+        ["smart-pointers"],
+        circular_reference_and_self_reference::circular_reference_and_self_reference
+    ),
+    chapter!(
+        error_handling,
+        "error handling",
+        ["error-handling"],
+        error_handling::error_handling
+    ),
+    chapter!(
+        self_referential_struct,
+        "self referential struct",
+        ["smart-pointers", "advanced"],
+        self_referential_struct::self_referential_struct
+    ),
+    chapter!(
+        global_variable,
+        "global variable",
+        ["advanced"],
+        global_variable::global_variable
+    ),
+    chapter!(
+        rust_common_pitfalls,
+        "rust common pitfalls",
+        ["advanced"],
+        rust_common_pitfalls::rust_common_pitfalls
+    ),
+    chapter!(
+        eq_partialeq,
+        "Eq & PartialEq",
+        ["traits"],
+        eq_partialeq::eq_partialeq
+    ),
+    chapter!(
+        unsafe_rust,
+        "unsafe rust",
+        ["advanced"],
+        unsafe_rust::unsafe_rust
+    ),
+];
+
+pub fn find(name: &str) -> Option<&'static Chapter> {
+    CHAPTERS.iter().find(|c| c.name == name)
+}
+
+pub fn with_tag(tag: &str) -> impl Iterator<Item = &'static Chapter> + '_ {
+    CHAPTERS.iter().filter(move |c| c.has_tag(tag))
+}
+
+// 所有出现过的标签，按首次出现的顺序去重
+pub fn tags() -> Vec<&'static str> {
+    let mut tags: Vec<&'static str> = Vec::new();
+    for tag in CHAPTERS.iter().flat_map(|c| c.tags.iter()) {
+        if !tags.contains(tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
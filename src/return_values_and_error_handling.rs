@@ -1,8 +1,14 @@
 use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
     fs::{self, File},
-    io::{self, ErrorKind, Read},
+    io::{self, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
 };
 
+use crate::panic_tools;
+
 pub fn return_values_and_error_handling() {
     // 可恢复错误，通常用于从系统全局角度来看可以接受的错误，例如处理用户的访问、操作等错误，这些错误只会影响某个用户自身的操作进程，而不会对系统的全局稳定性产生影响
     // 不可恢复错误，刚好相反，该错误通常是全局性或者系统性的错误，例如数组越界访问，系统启动时发生了影响启动流程的错误等等，这些错误的影响往往对于系统来说是致命的
@@ -30,6 +36,17 @@ pub fn return_values_and_error_handling() {
     // 还有一种情况，在展开过程中，如果展开本身 panic 了，那展开线程会终止，展开也随之停止。
     // 一旦线程展开被终止或者完成，最终的输出结果是取决于哪个线程 panic：对于 main 线程，操作系统提供的终止功能 core::intrinsics::abort() 会被调用，最终结束当前的 panic 进程；如果是其它子线程，那么子线程就会简单的终止，同时信息会在稍后通过 std::thread::join() 进行收集。
 
+    // 上面的流程可以借助 panic_tools 直接观察：run_isolated 通过 catch_unwind 拦截当前线程的 panic，
+    // spawn_worker 让子线程 panic，再通过 JoinHandle::join 收集 panic 的信息，主线程不受影响
+    let report = panic_tools::run_isolated(|| -> i32 { panic!("boom") }).unwrap_err();
+    println!("caught `{}` on thread '{}'", report.message, report.thread);
+    let report =
+        panic_tools::spawn_worker("worker", || -> i32 { panic!("worker failed") }).unwrap_err();
+    println!(
+        "joined `{}` from thread '{}'",
+        report.message, report.thread
+    );
+
     // 可恢复的错误 Result
 
     // enum Result<T, E> {
@@ -138,6 +155,26 @@ pub fn return_values_and_error_handling() {
     }
     // 从文件读取数据到字符串中，是比较常见的操作，因此 Rust 标准库为我们提供了 fs::read_to_string 函数，该函数内部会打开一个文件、创建 String、读取文件内容最后写入字符串并返回，因为该函数其实与本章讲的内容关系不大，因此放在最后来讲，其实只是我想震你们一下 :)
 
+    // 实战：配置文件加载器
+    // 把上面的内容组合起来：read_or_create 调用模块顶层带路径参数的 read_username_from_file，文件不存在时写入默认配置；
+    // 其它 ErrorKind 不再 panic，而是转换成 ConfigError 返回，语法错误和类型错误会带上文件名、行号和列号。
+    let text =
+        "# demo\nname = \"rust thinking\" # 引号内的 # 不是注释\nport = 8080\nverbose = yes\n";
+    let config = Config::parse("demo.conf", text).unwrap();
+    println!(
+        "name = {:?}, port = {}, verbose = {}",
+        config.get("name").unwrap(),
+        config.get_u32("port").unwrap(),
+        config.get_bool("verbose").unwrap()
+    );
+    match config.get_bool("port") {
+        Ok(v) => println!("port as bool = {}", v),
+        Err(e) => println!("{}", e),
+    }
+    if let Err(e) = Config::parse("broken.conf", "name = \"unterminated\n") {
+        println!("{}", e);
+    }
+
     // ? 用于 Option 的返回
     // ? 不仅仅可以用于 Result 的传播，还能用于 Option 的传播，再来回忆下 Option 的定义：
     fn first(arr: &[i32]) -> Option<&i32> {
@@ -155,3 +192,504 @@ pub fn return_values_and_error_handling() {
     // 上面代码展示了在链式调用中使用 ? 提前返回 None 的用法， .next 方法返回的是 Option 类型：如果返回 Some(&str)，那么继续调用 chars 方法,如果返回 None，则直接从整个函数中返回 None，不再继续进行链式调用。
     // 这样就能使用 ? 提前返回了，同时我们又一次看到了Box<dyn Error> 特征对象，因为 std::error:Error 是 Rust 中抽象层次最高的错误，其它标准库中的错误都实现了该特征，因此我们可以用该特征对象代表一切错误，就算 main 函数中调用任何标准库函数发生错误，都可以通过 Box<dyn Error> 这个特征对象进行返回.
 }
+
+/// 配置文件不存在时写入的默认内容。
+pub const DEFAULT_CONFIG: &str =
+    "# rust_thinking 默认配置\nname = \"rust thinking\"\nport = 8080\nverbose = false\n";
+
+/// 章节中 `read_username_from_file` 的 `?` 写法，文件路径由参数传入。
+pub fn read_username_from_file(path: &Path) -> Result<String, io::Error> {
+    let mut s = String::new();
+    File::open(path)?.read_to_string(&mut s)?;
+    Ok(s)
+}
+
+// 在 read_username_from_file 的基础上处理 NotFound：先用 default 创建文件，再把 default 作为内容返回，其它错误原样返回
+pub fn read_or_create(path: &Path, default: &str) -> Result<String, io::Error> {
+    match read_username_from_file(path) {
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            let mut created = File::create(path)?;
+            created.write_all(default.as_bytes())?;
+            Ok(default.to_string())
+        }
+        result => result,
+    }
+}
+
+/// 配置文件中的位置，行号和列号都从 1 开始，列号按字符计算。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file.display(), self.line, self.column)
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    PermissionDenied {
+        file: PathBuf,
+        source: io::Error,
+    },
+    // 文件内容不是合法的 UTF-8
+    InvalidData {
+        file: PathBuf,
+        source: io::Error,
+    },
+    Io {
+        file: PathBuf,
+        source: io::Error,
+    },
+    Syntax {
+        at: Location,
+        message: String,
+    },
+    Missing {
+        file: PathBuf,
+        key: String,
+    },
+    Type {
+        at: Location,
+        key: String,
+        expected: &'static str,
+        found: String,
+    },
+}
+
+impl ConfigError {
+    // 按照 ErrorKind 区分 I/O 错误，NotFound 已经在 read_or_create 中处理掉了
+    pub fn from_io(file: &Path, source: io::Error) -> ConfigError {
+        let file = file.to_path_buf();
+        match source.kind() {
+            ErrorKind::PermissionDenied => ConfigError::PermissionDenied { file, source },
+            ErrorKind::InvalidData => ConfigError::InvalidData { file, source },
+            _ => ConfigError::Io { file, source },
+        }
+    }
+
+    // 语法错误和类型错误的位置
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            ConfigError::Syntax { at, .. } | ConfigError::Type { at, .. } => Some(at),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::PermissionDenied { file, .. } => {
+                write!(f, "{}: permission denied", file.display())
+            }
+            ConfigError::InvalidData { file, .. } => {
+                write!(f, "{}: file is not valid UTF-8", file.display())
+            }
+            ConfigError::Io { file, .. } => write!(f, "{}: failed to read config", file.display()),
+            ConfigError::Syntax { at, message } => write!(f, "{}: {}", at, message),
+            ConfigError::Missing { file, key } => {
+                write!(f, "{}: missing key `{}`", file.display(), key)
+            }
+            ConfigError::Type {
+                at,
+                key,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{}: `{}` expected {}, found {:?}",
+                at, key, expected, found
+            ),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::PermissionDenied { source, .. }
+            | ConfigError::InvalidData { source, .. }
+            | ConfigError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: String,
+    line: usize,
+    column: usize,
+}
+
+/// `key = value` 格式的配置文件：
+///
+/// - 空行以及 `#`、`;` 开头的行是注释，未加引号的值中空白之后的 `#` 也会开启注释
+/// - 值可以用双引号包裹，引号内支持 `\"`、`\\`、`\n`、`\t` 转义
+/// - 同一个 key 只能出现一次
+///
+/// ```
+/// use rust_thinking::return_values_and_error_handling::Config;
+///
+/// let config = Config::parse("app.conf", "port = 8080 # http\nname = \" a#b \"\n").unwrap();
+/// assert_eq!(config.get_u32("port").unwrap(), 8080);
+/// assert_eq!(config.get("name"), Some(" a#b "));
+///
+/// let err = Config::parse("app.conf", "\n  port 8080\n").unwrap_err();
+/// assert_eq!(err.to_string(), "app.conf:2:3: expected `key = value`");
+/// ```
+#[derive(Debug)]
+pub struct Config {
+    file: PathBuf,
+    entries: HashMap<String, Entry>,
+}
+
+impl Config {
+    // 读取配置文件，文件不存在时用 DEFAULT_CONFIG 创建
+    pub fn load(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+        Config::load_or_create(path, DEFAULT_CONFIG)
+    }
+
+    pub fn load_or_create(path: impl AsRef<Path>, default: &str) -> Result<Config, ConfigError> {
+        let path = path.as_ref();
+        let text = read_or_create(path, default).map_err(|e| ConfigError::from_io(path, e))?;
+        Config::parse(path, &text)
+    }
+
+    pub fn parse(file: impl AsRef<Path>, text: &str) -> Result<Config, ConfigError> {
+        let file = file.as_ref().to_path_buf();
+        let mut entries: HashMap<String, Entry> = HashMap::new();
+
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            let at = |column: usize| Location {
+                file: file.clone(),
+                line,
+                column,
+            };
+            let syntax = |column: usize, message: String| ConfigError::Syntax {
+                at: at(column),
+                message,
+            };
+
+            let content = raw.trim_start();
+            let indent = raw.chars().count() - content.chars().count();
+            if content.is_empty() || content.starts_with('#') || content.starts_with(';') {
+                continue;
+            }
+
+            let (key, rest) = match content.split_once('=') {
+                Some(pair) => pair,
+                None => return Err(syntax(indent + 1, "expected `key = value`".to_string())),
+            };
+            let key = key.trim_end();
+            if key.is_empty()
+                || !key
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+            {
+                return Err(syntax(indent + 1, format!("invalid key `{}`", key)));
+            }
+
+            // 值在整行中的起始列
+            let value_start = rest.trim_start();
+            let column = indent + content.chars().count() - value_start.chars().count() + 1;
+            let value = parse_value(value_start)
+                .map_err(|(offset, message)| syntax(column + offset, message))?;
+
+            if let Some(first) = entries.get(key) {
+                return Err(syntax(
+                    indent + 1,
+                    format!(
+                        "duplicate key `{}` (first defined at line {})",
+                        key, first.line
+                    ),
+                ));
+            }
+            entries.insert(
+                key.to_string(),
+                Entry {
+                    value,
+                    line,
+                    column,
+                },
+            );
+        }
+
+        Ok(Config { file, entries })
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|e| e.value.as_str())
+    }
+
+    pub fn get_u32(&self, key: &str) -> Result<u32, ConfigError> {
+        let entry = self.entry(key)?;
+        entry
+            .value
+            .parse()
+            .map_err(|_| self.type_error(key, entry, "an unsigned integer"))
+    }
+
+    // 除了 true/false，也接受 yes/no、on/off、1/0
+    pub fn get_bool(&self, key: &str) -> Result<bool, ConfigError> {
+        let entry = self.entry(key)?;
+        match entry.value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok(true),
+            "false" | "no" | "off" | "0" => Ok(false),
+            _ => Err(self.type_error(key, entry, "a boolean")),
+        }
+    }
+
+    fn entry(&self, key: &str) -> Result<&Entry, ConfigError> {
+        self.entries.get(key).ok_or_else(|| ConfigError::Missing {
+            file: self.file.clone(),
+            key: key.to_string(),
+        })
+    }
+
+    fn type_error(&self, key: &str, entry: &Entry, expected: &'static str) -> ConfigError {
+        ConfigError::Type {
+            at: Location {
+                file: self.file.clone(),
+                line: entry.line,
+                column: entry.column,
+            },
+            key: key.to_string(),
+            expected,
+            found: entry.value.clone(),
+        }
+    }
+}
+
+// 解析等号右边的部分，出错时返回相对于值起始位置的列偏移和错误信息
+fn parse_value(s: &str) -> Result<String, (usize, String)> {
+    let rest = match s.strip_prefix('"') {
+        Some(rest) => rest,
+        None => {
+            let end = s
+                .char_indices()
+                .find(|&(i, c)| c == '#' && s[..i].ends_with(char::is_whitespace))
+                .map(|(i, _)| i)
+                .unwrap_or(s.len());
+            return Ok(s[..end].trim_end().to_string());
+        }
+    };
+
+    let mut value = String::new();
+    let mut chars = rest.chars().enumerate();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                let trailing: String = chars.map(|(_, c)| c).collect();
+                let trimmed = trailing.trim_start();
+                if !trimmed.is_empty() && !trimmed.starts_with('#') {
+                    let offset = i + 2 + trailing.chars().count() - trimmed.chars().count();
+                    return Err((
+                        offset,
+                        "unexpected characters after closing quote".to_string(),
+                    ));
+                }
+                return Ok(value);
+            }
+            '\\' => match chars.next() {
+                Some((_, 'n')) => value.push('\n'),
+                Some((_, 't')) => value.push('\t'),
+                Some((_, c @ ('"' | '\\'))) => value.push(c),
+                Some((j, c)) => return Err((j + 1, format!("unknown escape `\\{}`", c))),
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+    Err((0, "unterminated quoted value".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    // 每个测试使用独立的临时目录，测试并行执行时互不干扰
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "rust_thinking_config_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parses_comments_quotes_and_typed_values() {
+        let text = "\
+# comment
+; another comment
+
+name = \"say \\\"hi\\\"\\n\"   # trailing comment
+path=/usr/local/bin#not-a-comment
+port = 8080
+debug = Off
+empty =
+";
+        let config = Config::parse("t.conf", text).unwrap();
+        assert_eq!(config.get("name"), Some("say \"hi\"\n"));
+        assert_eq!(config.get("path"), Some("/usr/local/bin#not-a-comment"));
+        assert_eq!(config.get("empty"), Some(""));
+        assert_eq!(config.get_u32("port").unwrap(), 8080);
+        assert!(!config.get_bool("debug").unwrap());
+        assert_eq!(config.get("missing"), None);
+    }
+
+    #[test]
+    fn syntax_errors_carry_line_and_column() {
+        let cases = [
+            ("a = 1\n   oops\n", 2, 4, "expected `key = value`"),
+            ("  bad key = 1\n", 1, 3, "invalid key `bad key`"),
+            ("k = \"open\n", 1, 5, "unterminated quoted value"),
+            (
+                "k = \"v\" x\n",
+                1,
+                9,
+                "unexpected characters after closing quote",
+            ),
+            ("k = \"a\\q\"\n", 1, 8, "unknown escape `\\q`"),
+            (
+                "k = 1\n k = 2\n",
+                2,
+                2,
+                "duplicate key `k` (first defined at line 1)",
+            ),
+            (
+                "名字 = \"x\" y",
+                1,
+                10,
+                "unexpected characters after closing quote",
+            ),
+        ];
+        for (text, line, column, message) in cases {
+            match Config::parse("t.conf", text).unwrap_err() {
+                ConfigError::Syntax { at, message: m } => {
+                    assert_eq!(
+                        (at.line, at.column, m.as_str()),
+                        (line, column, message),
+                        "{:?}",
+                        text
+                    );
+                    assert_eq!(at.file, Path::new("t.conf"));
+                }
+                e => panic!("unexpected error for {:?}: {}", text, e),
+            }
+        }
+    }
+
+    #[test]
+    fn typed_getters_report_value_location() {
+        let config = Config::parse("t.conf", "\nport =   eighty\nflag = maybe\n").unwrap();
+        let err = config.get_u32("port").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "t.conf:2:10: `port` expected an unsigned integer, found \"eighty\""
+        );
+        assert_eq!(
+            config
+                .get_bool("flag")
+                .unwrap_err()
+                .location()
+                .unwrap()
+                .column,
+            8
+        );
+        assert!(matches!(
+            config.get_u32("nope").unwrap_err(),
+            ConfigError::Missing { .. }
+        ));
+    }
+
+    #[test]
+    fn missing_file_is_created_with_defaults() {
+        let dir = temp_dir("create");
+        let path = dir.join("app.conf");
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), DEFAULT_CONFIG);
+        assert_eq!(config.get("name"), Some("rust thinking"));
+        assert_eq!(config.get_u32("port").unwrap(), 8080);
+        assert!(!config.get_bool("verbose").unwrap());
+
+        // 第二次加载读取的是已经存在的文件
+        fs::write(&path, "port = 9090\n").unwrap();
+        assert_eq!(Config::load(&path).unwrap().get_u32("port").unwrap(), 9090);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_utf8_is_invalid_data() {
+        let dir = temp_dir("utf8");
+        let path = dir.join("app.conf");
+        fs::write(&path, [b'k', b'=', 0xff, 0xfe]).unwrap();
+
+        let err = Config::load(&path).unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidData { .. }), "{:?}", err);
+        assert!(err.source().is_some());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn io_errors_are_classified_by_kind() {
+        let file = Path::new("x.conf");
+        let denied = ConfigError::from_io(file, io::Error::from(ErrorKind::PermissionDenied));
+        assert!(matches!(denied, ConfigError::PermissionDenied { .. }));
+        assert_eq!(denied.to_string(), "x.conf: permission denied");
+        let other = ConfigError::from_io(file, io::Error::from(ErrorKind::Interrupted));
+        assert!(matches!(other, ConfigError::Io { .. }));
+
+        // 目录无法作为文件读取，也不会被当作 NotFound 覆盖
+        let dir = temp_dir("dir");
+        assert!(matches!(
+            Config::load(&dir).unwrap_err(),
+            ConfigError::Io { .. }
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_file_is_permission_denied() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir("perm");
+        let path = dir.join("app.conf");
+        fs::write(&path, "port = 1\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o000)).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0);
+
+        // root 用户不受文件权限限制，造不出 PermissionDenied，这个测试只能跳过，不算覆盖了这条路径
+        let readable = File::open(&path).is_ok();
+        let result = Config::load(&path);
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        if readable {
+            eprintln!("skipped unreadable_file_is_permission_denied: file permissions are not enforced for this user (root?)");
+            return;
+        }
+
+        assert!(matches!(
+            result.unwrap_err(),
+            ConfigError::PermissionDenied { .. }
+        ));
+    }
+}
@@ -0,0 +1,184 @@
+use std::time::{Duration, Instant};
+
+use crate::{panic_tools, registry::Chapter};
+
+// 章节运行器
+// 每个章节的入口函数都通过 panic_tools::run_isolated 执行，某个章节里失败的 assert_eq! 或者 RefCell 的重复可变借用只会让这一个章节失败，
+// 后面的章节依然会继续运行，最后统一输出一张汇总表。
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PanicDetail {
+    pub message: String,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Passed,
+    Panicked(PanicDetail),
+}
+
+pub struct ChapterResult {
+    pub chapter: &'static Chapter,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+impl ChapterResult {
+    pub fn passed(&self) -> bool {
+        self.outcome == Outcome::Passed
+    }
+}
+
+pub fn run_chapter(chapter: &'static Chapter) -> ChapterResult {
+    let start = Instant::now();
+    let result = panic_tools::run_isolated(chapter.entry);
+    let elapsed = start.elapsed();
+
+    let outcome = match result {
+        Ok(()) => Outcome::Passed,
+        Err(report) => Outcome::Panicked(PanicDetail {
+            message: report.message,
+            location: report.location,
+        }),
+    };
+
+    ChapterResult {
+        chapter,
+        outcome,
+        elapsed,
+    }
+}
+
+pub fn run_chapters(chapters: &[&'static Chapter]) -> Vec<ChapterResult> {
+    chapters
+        .iter()
+        .map(|chapter| {
+            println!("learn {}", chapter.title);
+            let result = run_chapter(chapter);
+            if let Outcome::Panicked(detail) = &result.outcome {
+                eprintln!(
+                    "chapter `{}` panicked at {}: {}",
+                    chapter.name,
+                    detail.location.as_deref().unwrap_or("<unknown>"),
+                    detail.message
+                );
+            }
+            result
+        })
+        .collect()
+}
+
+pub fn summary(results: &[ChapterResult]) -> String {
+    let name_width = results
+        .iter()
+        .map(|r| r.chapter.name.len())
+        .chain(Some("chapter".len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut out = format!(
+        "{:<name_width$}  {:<8}  {:>10}  location\n",
+        "chapter",
+        "status",
+        "time",
+        name_width = name_width
+    );
+    for result in results {
+        let (status, location) = match &result.outcome {
+            Outcome::Passed => ("passed", String::new()),
+            Outcome::Panicked(detail) => (
+                "panicked",
+                format!(
+                    "{} ({})",
+                    detail.location.as_deref().unwrap_or("<unknown>"),
+                    detail.message
+                ),
+            ),
+        };
+        out.push_str(&format!(
+            "{:<name_width$}  {:<8}  {:>8.2}ms  {}\n",
+            result.chapter.name,
+            status,
+            result.elapsed.as_secs_f64() * 1000.0,
+            location,
+            name_width = name_width
+        ));
+    }
+
+    let failed = results.iter().filter(|r| !r.passed()).count();
+    out.push_str(&format!(
+        "\n{} passed, {} panicked, {:.2}ms total\n",
+        results.len() - failed,
+        failed,
+        results
+            .iter()
+            .map(|r| r.elapsed.as_secs_f64() * 1000.0)
+            .sum::<f64>()
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn ok() {}
+
+    fn failing_assert() {
+        assert_eq!(1 + 1, 3, "math is broken");
+    }
+
+    fn double_borrow() {
+        let c = RefCell::new(1);
+        let _a = c.borrow();
+        let _b = c.borrow_mut();
+    }
+
+    static OK: Chapter = Chapter {
+        name: "ok",
+        title: "ok",
+        tags: &[],
+        entry: ok,
+    };
+    static FAILING_ASSERT: Chapter = Chapter {
+        name: "failing_assert",
+        title: "failing assert",
+        tags: &[],
+        entry: failing_assert,
+    };
+    static DOUBLE_BORROW: Chapter = Chapter {
+        name: "double_borrow",
+        title: "double borrow",
+        tags: &[],
+        entry: double_borrow,
+    };
+
+    #[test]
+    fn panics_are_isolated_per_chapter() {
+        let results = run_chapters(&[&FAILING_ASSERT, &DOUBLE_BORROW, &OK]);
+        assert_eq!(results.len(), 3);
+        assert!(!results[0].passed());
+        assert!(!results[1].passed());
+        assert!(results[2].passed());
+
+        match &results[0].outcome {
+            Outcome::Panicked(detail) => {
+                assert!(detail.message.contains("math is broken"));
+                assert!(detail.location.as_deref().unwrap().contains("runner.rs"));
+            }
+            Outcome::Passed => unreachable!(),
+        }
+        match &results[1].outcome {
+            Outcome::Panicked(detail) => assert!(detail.message.contains("borrowed")),
+            Outcome::Passed => unreachable!(),
+        }
+
+        let table = summary(&results);
+        assert!(table.contains("1 passed, 2 panicked"));
+        assert!(table
+            .lines()
+            .any(|l| l.starts_with("double_borrow") && l.contains("panicked")));
+    }
+}
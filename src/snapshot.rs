@@ -0,0 +1,336 @@
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::registry::Chapter;
+
+// 章节输出的快照测试
+// 像 learn_drop、circular_reference_and_self_reference 这样的章节，价值全在打印出来的内容上(drop 的顺序、Rc::strong_count 的值)，
+// 这里把每个章节的输出捕获下来保存到 snapshots/<章节名>.txt，之后通过 verify 比对当前输出与快照，不一致时打印 unified diff。
+
+pub const SNAPSHOT_DIR: &str = "snapshots";
+
+// 输出本身就不稳定的章节：打印 HashMap 的遍历顺序、多线程交错打印，这些章节不参与快照比对
+pub const NONDETERMINISTIC: &[&str] = &["learn_iterator", "hash_map", "rc_arc"];
+
+// 捕获章节输出的方式，捕获到的内容写入调用方提供的 writer：可以是快照文件，也可以是内存中的 Vec<u8>
+pub trait Capture {
+    fn capture(&self, chapter: &Chapter, out: &mut dyn Write) -> io::Result<()>;
+}
+
+// 章节里到处都是 println!，没法在进程内重定向，因此用 `exec <章节>` 重新启动自身，在子进程中运行章节并收集 stdout/stderr
+pub struct Subprocess {
+    exe: PathBuf,
+}
+
+impl Subprocess {
+    pub fn current() -> io::Result<Subprocess> {
+        Ok(Subprocess {
+            exe: env::current_exe()?,
+        })
+    }
+}
+
+impl Capture for Subprocess {
+    fn capture(&self, chapter: &Chapter, out: &mut dyn Write) -> io::Result<()> {
+        let output = Command::new(&self.exe)
+            .args(["exec", chapter.name])
+            .output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "chapter exited with {}:\n{}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        out.write_all(normalize(&String::from_utf8_lossy(&output.stdout)).as_bytes())?;
+        if !output.stderr.is_empty() {
+            out.write_all(b"--- stderr ---\n")?;
+            out.write_all(normalize(&String::from_utf8_lossy(&output.stderr)).as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+// 打印出来的指针地址每次运行都不同，统一替换成 0x<addr>
+pub fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find("0x") {
+        out.push_str(&rest[..pos]);
+        let digits = rest[pos + 2..]
+            .bytes()
+            .take_while(|b| b.is_ascii_hexdigit())
+            .count();
+        if digits >= 4 {
+            out.push_str("0x<addr>");
+        } else {
+            out.push_str(&rest[pos..pos + 2 + digits]);
+        }
+        rest = &rest[pos + 2 + digits..];
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn snapshot_path(dir: &Path, chapter: &Chapter) -> PathBuf {
+    dir.join(format!("{}.txt", chapter.name))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    Matched,
+    Written,
+    Mismatched,
+    Skipped,
+    Failed,
+}
+
+// 逐个章节比对快照；update 为 true 时直接用当前输出覆盖快照，没有快照的章节总是会写入新快照
+pub fn verify(
+    chapters: &[&Chapter],
+    dir: &Path,
+    update: bool,
+    capture: &dyn Capture,
+    report: &mut dyn Write,
+) -> io::Result<Vec<Status>> {
+    fs::create_dir_all(dir)?;
+
+    let mut statuses = Vec::with_capacity(chapters.len());
+    for chapter in chapters {
+        if NONDETERMINISTIC.contains(&chapter.name) {
+            writeln!(
+                report,
+                "skip     {} (output is not deterministic)",
+                chapter.name
+            )?;
+            statuses.push(Status::Skipped);
+            continue;
+        }
+
+        let mut current = Vec::new();
+        if let Err(e) = capture.capture(chapter, &mut current) {
+            writeln!(report, "FAILED   {}: {}", chapter.name, e)?;
+            statuses.push(Status::Failed);
+            continue;
+        }
+        let current = String::from_utf8_lossy(&current).into_owned();
+
+        let path = snapshot_path(dir, chapter);
+        let golden = match fs::read_to_string(&path) {
+            Ok(golden) => Some(golden),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+
+        let status = match golden {
+            Some(golden) if golden == current => Status::Matched,
+            Some(golden) if !update => {
+                let name = path.display().to_string();
+                report.write_all(unified_diff(&golden, &current, &name, "current").as_bytes())?;
+                Status::Mismatched
+            }
+            _ => {
+                fs::write(&path, &current)?;
+                Status::Written
+            }
+        };
+
+        let label = match status {
+            Status::Matched => "ok      ",
+            Status::Written => "written ",
+            _ => "MISMATCH",
+        };
+        writeln!(report, "{} {}", label, chapter.name)?;
+        statuses.push(status);
+    }
+    Ok(statuses)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+// 基于最长公共子序列的逐行 diff，返回 (操作, 旧行号, 新行号) 序列
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<(Op, usize, usize)> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::with_capacity(n + m);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            ops.push((Op::Equal, i, j));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push((Op::Delete, i, j));
+            i += 1;
+        } else {
+            ops.push((Op::Insert, i, j));
+            j += 1;
+        }
+    }
+    ops
+}
+
+const CONTEXT: usize = 3;
+
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    let changed: Vec<usize> = (0..ops.len()).filter(|&k| ops[k].0 != Op::Equal).collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    // 把相距不超过 2 * CONTEXT 的改动合并进同一个 hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &k in &changed {
+        let start = k.saturating_sub(CONTEXT);
+        let end = (k + CONTEXT + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_name, new_name);
+    for (start, end) in hunks {
+        let slice = &ops[start..end];
+        let old_count = slice.iter().filter(|op| op.0 != Op::Insert).count();
+        let new_count = slice.iter().filter(|op| op.0 != Op::Delete).count();
+        let (_, old_start, new_start) = slice[0];
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            if old_count == 0 {
+                old_start
+            } else {
+                old_start + 1
+            },
+            old_count,
+            if new_count == 0 {
+                new_start
+            } else {
+                new_start + 1
+            },
+            new_count
+        ));
+        for &(op, i, j) in slice {
+            match op {
+                Op::Equal => out.push_str(&format!(" {}\n", old_lines[i])),
+                Op::Delete => out.push_str(&format!("-{}\n", old_lines[i])),
+                Op::Insert => out.push_str(&format!("+{}\n", new_lines[j])),
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn noop() {}
+
+    static DROP_ORDER: Chapter = Chapter {
+        name: "drop_order",
+        title: "drop order",
+        tags: &[],
+        entry: noop,
+    };
+
+    struct Fake(RefCell<String>);
+
+    impl Capture for Fake {
+        fn capture(&self, _: &Chapter, out: &mut dyn Write) -> io::Result<()> {
+            out.write_all(self.0.borrow().as_bytes())
+        }
+    }
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        let old = "drop a\ndrop b\ndrop c\n";
+        let new = "drop a\ndrop c\ndrop b\n";
+        let diff = unified_diff(old, new, "golden", "current");
+        assert_eq!(
+            diff,
+            "--- golden\n+++ current\n@@ -1,3 +1,3 @@\n drop a\n-drop b\n drop c\n+drop b\n"
+        );
+        assert_eq!(unified_diff(old, old, "golden", "current"), "");
+    }
+
+    #[test]
+    fn diff_splits_distant_changes_into_hunks() {
+        let old: String = (1..=20).map(|i| format!("{}\n", i)).collect();
+        let new: String = (1..=20)
+            .map(|i| match i {
+                2 => "two\n".to_string(),
+                19 => "nineteen\n".to_string(),
+                i => format!("{}\n", i),
+            })
+            .collect();
+        let diff = unified_diff(&old, &new, "a", "b");
+        assert_eq!(diff.matches("@@ ").count(), 2);
+        assert!(diff.contains("@@ -1,5 +1,5 @@\n 1\n-2\n+two\n"));
+        assert!(diff.contains("-19\n+nineteen\n 20\n"));
+    }
+
+    #[test]
+    fn normalize_pointer_addresses() {
+        assert_eq!(
+            normalize("The 12 bytes at 0x5604FEFAF63F stored, 0x1f"),
+            "The 12 bytes at 0x<addr> stored, 0x1f"
+        );
+    }
+
+    #[test]
+    fn verify_writes_compares_and_updates() {
+        let dir = env::temp_dir().join(format!("rust_thinking_snapshot_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let fake = Fake(RefCell::new("strong = 1\n".to_string()));
+        let chapters = [&DROP_ORDER];
+        let mut report = Vec::new();
+
+        let statuses = verify(&chapters, &dir, false, &fake, &mut report).unwrap();
+        assert_eq!(statuses, vec![Status::Written]);
+
+        let statuses = verify(&chapters, &dir, false, &fake, &mut report).unwrap();
+        assert_eq!(statuses, vec![Status::Matched]);
+
+        *fake.0.borrow_mut() = "strong = 2\n".to_string();
+        let mut report = Vec::new();
+        let statuses = verify(&chapters, &dir, false, &fake, &mut report).unwrap();
+        assert_eq!(statuses, vec![Status::Mismatched]);
+        let report = String::from_utf8(report).unwrap();
+        assert!(report.contains("-strong = 1\n+strong = 2\n"));
+
+        let statuses = verify(&chapters, &dir, true, &fake, &mut Vec::new()).unwrap();
+        assert_eq!(statuses, vec![Status::Written]);
+        assert_eq!(
+            fs::read_to_string(snapshot_path(&dir, &DROP_ORDER)).unwrap(),
+            "strong = 2\n"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
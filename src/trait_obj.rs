@@ -47,52 +47,7 @@ pub fn trait_obj() {
     // dyn 关键字只用在特征对象的类型声明上，在创建时无需使用 dyn
     // 使用泛型和 特征约束，因为实现更清晰，且性能更好(特征对象，需要在运行时从 vtable 动态查找需要调用的方法)。
 
-    pub trait Draw1 {
-        fn draw(&self);
-    }
-
-    pub struct Button {
-        pub width: u32,
-        pub height: u32,
-        pub label: String,
-    }
-
-    impl Draw1 for Button {
-        fn draw(&self) {
-            println!(
-                "button draw 1, weight: {}, height: {}, label: {}",
-                self.width, self.height, self.label
-            );
-        }
-    }
-
-    struct SelectBox {
-        width: u32,
-        height: u32,
-        options: Vec<String>,
-    }
-
-    impl Draw1 for SelectBox {
-        fn draw(&self) {
-            println!(
-                "SelectBox draw 1, weight: {}, height: {}, options: {:?}",
-                self.width, self.height, self.options
-            );
-        }
-    }
-
-    pub struct Screen {
-        pub components: Vec<Box<dyn Draw1>>,
-    }
-
-    impl Screen {
-        pub fn run(&self) {
-            for component in self.components.iter() {
-                component.draw();
-            }
-        }
-    }
-
+    // Draw1 特征以及 Button、SelectBox、Screen 定义在模块顶层
     let screen = Screen {
         components: vec![
             Box::new(SelectBox {
@@ -158,3 +113,72 @@ pub fn trait_obj() {
 
     // 对象安全对于特征对象是必须的，因为一旦有了特征对象，就不再需要知道实现该特征的具体类型是什么了。如果特征方法返回了具体的 Self 类型，但是特征对象忘记了其真正的类型，那这个 Self 就非常尴尬，因为没人知道它是谁了。但是对于泛型类型参数来说，当使用特征时其会放入具体的类型参数：此具体类型变成了实现该特征的类型的一部分。而当使用特征对象时其具体类型被抹去了，故而无从得知放入泛型参数类型到底是什么。
 }
+
+/// 可以被 `Screen` 绘制的组件。
+pub trait Draw1 {
+    fn draw(&self);
+}
+
+pub struct Button {
+    pub width: u32,
+    pub height: u32,
+    pub label: String,
+}
+
+impl Draw1 for Button {
+    fn draw(&self) {
+        println!(
+            "button draw 1, weight: {}, height: {}, label: {}",
+            self.width, self.height, self.label
+        );
+    }
+}
+
+pub struct SelectBox {
+    pub width: u32,
+    pub height: u32,
+    pub options: Vec<String>,
+}
+
+impl Draw1 for SelectBox {
+    fn draw(&self) {
+        println!(
+            "SelectBox draw 1, weight: {}, height: {}, options: {:?}",
+            self.width, self.height, self.options
+        );
+    }
+}
+
+/// 屏幕持有一组 `Box<dyn Draw1>` 特征对象，不同类型的组件可以放在同一个 Vec 中，运行时通过 vtable 分发到各自的 `draw` 实现。
+///
+/// ```
+/// use rust_thinking::trait_obj::{Button, Screen, SelectBox};
+///
+/// let screen = Screen {
+///     components: vec![
+///         Box::new(SelectBox {
+///             width: 75,
+///             height: 10,
+///             options: vec![String::from("Yes"), String::from("No")],
+///         }),
+///         Box::new(Button {
+///             width: 50,
+///             height: 10,
+///             label: String::from("OK"),
+///         }),
+///     ],
+/// };
+/// assert_eq!(screen.components.len(), 2);
+/// screen.run();
+/// ```
+pub struct Screen {
+    pub components: Vec<Box<dyn Draw1>>,
+}
+
+impl Screen {
+    pub fn run(&self) {
+        for component in self.components.iter() {
+            component.draw();
+        }
+    }
+}
@@ -0,0 +1,589 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+// 带单位的物理量
+// deep_trait 章节用 Add<Meters> for Millimeters 演示了默认类型参数，newtype 章节用 Meters/MilliMeters 演示了类型异化，
+// 但它们都只能表示一种单位、支持一种运算。这里把单位也放进类型里：
+// 1. 单位是零大小的标记类型(Meter、Second……)，Quantity<U, T> 只在运行时保存数值，单位只在编译期存在
+// 2. 每个单位属于一种量纲(Length、Time……)，只有量纲相同的量才能相加减，Meter + Second 无法通过编译
+// 3. 量与量相乘除时，结果的单位由标记类型上的 Mul/Div 实现给出，例如 Meter / Second = MeterPerSecond
+// 4. 单位换算必须显式调用：to 只允许精确换算(例如 km -> m)，to_lossy 允许截断或舍入(例如 mm -> m)
+// 5. 整数数值换算时可能超出类型的范围，checked_to、checked_to_lossy 返回 None；to、to_lossy 以及混合单位的加减法在溢出时 panic，不会静默回绕
+
+/// 量纲，只作为类型标记使用。
+pub trait Dimension {}
+
+pub enum Length {}
+pub enum Mass {}
+pub enum Time {}
+pub enum Area {}
+pub enum Velocity {}
+pub enum Acceleration {}
+pub enum Force {}
+
+impl Dimension for Length {}
+impl Dimension for Mass {}
+impl Dimension for Time {}
+impl Dimension for Area {}
+impl Dimension for Velocity {}
+impl Dimension for Acceleration {}
+impl Dimension for Force {}
+
+/// 单位：所属的量纲、显示的后缀，以及相对于该量纲基本单位的比例 `NUM / DEN`。
+pub trait Unit: Copy + Default {
+    type Dim: Dimension;
+    const SUFFIX: &'static str;
+    const NUM: i64;
+    const DEN: i64;
+
+    fn of<T>(value: T) -> Quantity<Self, T> {
+        Quantity::new(value)
+    }
+}
+
+/// 可以把 `Self` 精确地换算成 `V`：换算比例是整数，整数数值也不会丢失精度。
+pub trait ExactInto<V: Unit>: Unit {}
+
+impl<U: Unit> ExactInto<U> for U {}
+
+/// 数值类型需要支持的运算，以及带溢出检查的换算。
+pub trait Scalar:
+    Copy
+    + PartialEq
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    /// 计算 `self * num / den`，整数向零截断，结果超出类型范围时返回 `None`。
+    fn checked_scale(self, num: i64, den: i64) -> Option<Self>;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+}
+
+// 整数先扩展成 i128 再乘除：i64 的比例乘以任何 64 位整数都不会溢出 i128，只需要检查结果能否放回原类型
+macro_rules! int_scalar {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                fn checked_scale(self, num: i64, den: i64) -> Option<$t> {
+                    let scaled = i128::from(self) * i128::from(num) / i128::from(den);
+                    <$t>::try_from(scaled).ok()
+                }
+
+                fn checked_add(self, rhs: $t) -> Option<$t> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: $t) -> Option<$t> {
+                    <$t>::checked_sub(self, rhs)
+                }
+            }
+        )*
+        scalar_ops!($($t),*);
+    };
+}
+
+// 浮点数没有溢出回绕，超出范围时得到无穷大，按 IEEE 754 的规则处理
+macro_rules! float_scalar {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                fn checked_scale(self, num: i64, den: i64) -> Option<$t> {
+                    Some(self * num as $t / den as $t)
+                }
+
+                fn checked_add(self, rhs: $t) -> Option<$t> {
+                    Some(self + rhs)
+                }
+
+                fn checked_sub(self, rhs: $t) -> Option<$t> {
+                    Some(self - rhs)
+                }
+            }
+        )*
+        scalar_ops!($($t),*);
+    };
+}
+
+macro_rules! scalar_ops {
+    ($($t:ty),*) => {
+        $(
+
+            impl<U: Unit> Mul<$t> for Quantity<U, $t> {
+                type Output = Quantity<U, $t>;
+
+                fn mul(self, rhs: $t) -> Quantity<U, $t> {
+                    Quantity::new(self.value * rhs)
+                }
+            }
+
+            impl<U: Unit> Mul<Quantity<U, $t>> for $t {
+                type Output = Quantity<U, $t>;
+
+                fn mul(self, rhs: Quantity<U, $t>) -> Quantity<U, $t> {
+                    Quantity::new(self * rhs.value)
+                }
+            }
+
+            impl<U: Unit> Div<$t> for Quantity<U, $t> {
+                type Output = Quantity<U, $t>;
+
+                fn div(self, rhs: $t) -> Quantity<U, $t> {
+                    Quantity::new(self.value / rhs)
+                }
+            }
+        )*
+    };
+}
+
+int_scalar!(i32, i64, u32, u64);
+float_scalar!(f32, f64);
+
+macro_rules! units {
+    ($($name:ident: $dim:ty, $suffix:literal, $num:literal / $den:literal;)*) => {
+        $(
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name;
+
+            impl Unit for $name {
+                type Dim = $dim;
+                const SUFFIX: &'static str = $suffix;
+                const NUM: i64 = $num;
+                const DEN: i64 = $den;
+            }
+        )*
+    };
+}
+
+units! {
+    Millimeter: Length, "mm", 1 / 1000;
+    Meter: Length, "m", 1 / 1;
+    Kilometer: Length, "km", 1000 / 1;
+    Gram: Mass, "g", 1 / 1000;
+    Kilogram: Mass, "kg", 1 / 1;
+    Millisecond: Time, "ms", 1 / 1000;
+    Second: Time, "s", 1 / 1;
+    Minute: Time, "min", 60 / 1;
+    Hour: Time, "h", 3600 / 1;
+    SquareMeter: Area, "m²", 1 / 1;
+    MeterPerSecond: Velocity, "m/s", 1 / 1;
+    KilometerPerHour: Velocity, "km/h", 5 / 18;
+    MeterPerSecondSquared: Acceleration, "m/s²", 1 / 1;
+    Newton: Force, "N", 1 / 1;
+}
+
+// 精确换算只能从大单位换到小单位
+macro_rules! exact {
+    ($($from:ident => $($to:ident),+;)*) => {
+        $($(impl ExactInto<$to> for $from {})+)*
+    };
+}
+
+exact! {
+    Meter => Millimeter;
+    Kilometer => Meter, Millimeter;
+    Kilogram => Gram;
+    Second => Millisecond;
+    Minute => Second, Millisecond;
+    Hour => Minute, Second, Millisecond;
+}
+
+// 单位之间的乘除关系，定义在标记类型上，Quantity 的乘除直接沿用
+macro_rules! derived {
+    ($($lhs:ident $op:tt $rhs:ident = $out:ident;)*) => {
+        $(derived!(@one $lhs $op $rhs = $out);)*
+    };
+    (@one $lhs:ident * $rhs:ident = $out:ident) => {
+        impl Mul<$rhs> for $lhs {
+            type Output = $out;
+
+            fn mul(self, _: $rhs) -> $out {
+                $out
+            }
+        }
+    };
+    (@one $lhs:ident / $rhs:ident = $out:ident) => {
+        impl Div<$rhs> for $lhs {
+            type Output = $out;
+
+            fn div(self, _: $rhs) -> $out {
+                $out
+            }
+        }
+    };
+}
+
+derived! {
+    Meter * Meter = SquareMeter;
+    SquareMeter / Meter = Meter;
+    Meter / Second = MeterPerSecond;
+    Meter / MeterPerSecond = Second;
+    MeterPerSecond * Second = Meter;
+    Second * MeterPerSecond = Meter;
+    Kilometer / Hour = KilometerPerHour;
+    Kilometer / KilometerPerHour = Hour;
+    KilometerPerHour * Hour = Kilometer;
+    Hour * KilometerPerHour = Kilometer;
+    MeterPerSecond / Second = MeterPerSecondSquared;
+    MeterPerSecondSquared * Second = MeterPerSecond;
+    Kilogram * MeterPerSecondSquared = Newton;
+    MeterPerSecondSquared * Kilogram = Newton;
+    Newton / Kilogram = MeterPerSecondSquared;
+    Newton / MeterPerSecondSquared = Kilogram;
+}
+
+/// 以 `U` 为单位、以 `T` 为数值类型的物理量，大小和 `T` 相同。
+///
+/// ```
+/// use rust_thinking::units::{Kilometer, Meter, Millimeter, Second, Unit};
+///
+/// let total = Millimeter::of(10) + Meter::of(5);
+/// assert_eq!(total.to_string(), "5010 mm");
+///
+/// let speed = Meter::of(100.0) / Second::of(8.0);
+/// assert_eq!(format!("{:.1}", speed), "12.5 m/s");
+///
+/// assert_eq!(Kilometer::of(3).to::<Meter>().value(), 3000);
+/// assert_eq!(Meter::of(2500).to_lossy::<Kilometer>().value(), 2);
+/// ```
+///
+/// 量纲不同的量不能相加：
+///
+/// ```compile_fail
+/// use rust_thinking::units::{Meter, Second, Unit};
+///
+/// let _ = Meter::of(1.0) + Second::of(1.0);
+/// ```
+///
+/// 会丢失精度的换算必须显式调用 `to_lossy`，不能隐式发生：
+///
+/// ```compile_fail
+/// use rust_thinking::units::{Meter, Millimeter, Unit};
+///
+/// let _ = Meter::of(1) + Millimeter::of(500);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Quantity<U, T = f64> {
+    value: T,
+    unit: PhantomData<U>,
+}
+
+impl<U: Unit, T> Quantity<U, T> {
+    pub fn new(value: T) -> Quantity<U, T> {
+        Quantity {
+            value,
+            unit: PhantomData,
+        }
+    }
+
+    pub fn into_value(self) -> T {
+        self.value
+    }
+}
+
+impl<U: Unit, T: Scalar> Quantity<U, T> {
+    pub fn value(&self) -> T {
+        self.value
+    }
+
+    // 精确换算，只有实现了 ExactInto 的单位对才能调用，结果超出 T 的范围时 panic
+    pub fn to<V: Unit>(self) -> Quantity<V, T>
+    where
+        U: ExactInto<V>,
+    {
+        self.checked_to().unwrap_or_else(|| overflow::<U, V>("to"))
+    }
+
+    /// 精确换算，结果超出 `T` 的范围时返回 `None`。
+    ///
+    /// ```
+    /// use rust_thinking::units::{Kilometer, Millimeter, Unit};
+    ///
+    /// let near = Kilometer::of(2_000i32).checked_to::<Millimeter>();
+    /// assert_eq!(near, Some(Millimeter::of(2_000_000_000)));
+    /// // 3000 km = 3e9 mm，超出了 i32 的范围
+    /// assert_eq!(Kilometer::of(3_000i32).checked_to::<Millimeter>(), None);
+    /// ```
+    pub fn checked_to<V: Unit>(self) -> Option<Quantity<V, T>>
+    where
+        U: ExactInto<V>,
+    {
+        let (num, den) = ratio::<U, V>();
+        debug_assert_eq!(den, 1, "{} -> {} is not exact", U::SUFFIX, V::SUFFIX);
+        self.value.checked_scale(num, den).map(Quantity::new)
+    }
+
+    // 同一量纲内的任意换算，整数会向零截断，浮点数会有舍入误差，结果超出 T 的范围时 panic
+    pub fn to_lossy<V: Unit<Dim = U::Dim>>(self) -> Quantity<V, T> {
+        self.checked_to_lossy()
+            .unwrap_or_else(|| overflow::<U, V>("to_lossy"))
+    }
+
+    // 中间结果不会溢出，只有最终结果超出 T 的范围时才返回 None
+    pub fn checked_to_lossy<V: Unit<Dim = U::Dim>>(self) -> Option<Quantity<V, T>> {
+        let (num, den) = ratio::<U, V>();
+        self.value.checked_scale(num, den).map(Quantity::new)
+    }
+
+    /// 混合单位的加法，换算或相加溢出时返回 `None`。
+    pub fn checked_add<V: ExactInto<U>>(self, rhs: Quantity<V, T>) -> Option<Quantity<U, T>> {
+        let rhs = rhs.checked_to::<U>()?;
+        self.value.checked_add(rhs.value).map(Quantity::new)
+    }
+
+    pub fn checked_sub<V: ExactInto<U>>(self, rhs: Quantity<V, T>) -> Option<Quantity<U, T>> {
+        let rhs = rhs.checked_to::<U>()?;
+        self.value.checked_sub(rhs.value).map(Quantity::new)
+    }
+}
+
+fn overflow<U: Unit, V: Unit>(op: &str) -> ! {
+    panic!(
+        "{}: {} -> {} overflows the scalar type",
+        op,
+        U::SUFFIX,
+        V::SUFFIX
+    )
+}
+
+// U -> V 的换算比例，约分到最简
+fn ratio<U: Unit, V: Unit>() -> (i64, i64) {
+    let num = U::NUM * V::DEN;
+    let den = U::DEN * V::NUM;
+    let g = gcd(num, den);
+    (num / g, den / g)
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+// 右侧可以是另一个单位，只要它能精确换算成左侧的单位，结果沿用左侧的单位
+impl<U: Unit, V: ExactInto<U>, T: Scalar> Add<Quantity<V, T>> for Quantity<U, T> {
+    type Output = Quantity<U, T>;
+
+    fn add(self, rhs: Quantity<V, T>) -> Quantity<U, T> {
+        self.checked_add(rhs)
+            .expect("overflow when adding quantities")
+    }
+}
+
+impl<U: Unit, V: ExactInto<U>, T: Scalar> Sub<Quantity<V, T>> for Quantity<U, T> {
+    type Output = Quantity<U, T>;
+
+    fn sub(self, rhs: Quantity<V, T>) -> Quantity<U, T> {
+        self.checked_sub(rhs)
+            .expect("overflow when subtracting quantities")
+    }
+}
+
+impl<U: Unit, T: Neg<Output = T>> Neg for Quantity<U, T> {
+    type Output = Quantity<U, T>;
+
+    fn neg(self) -> Quantity<U, T> {
+        Quantity::new(-self.value)
+    }
+}
+
+impl<U, V, T> Mul<Quantity<V, T>> for Quantity<U, T>
+where
+    U: Unit + Mul<V>,
+    V: Unit,
+    U::Output: Unit,
+    T: Scalar,
+{
+    type Output = Quantity<U::Output, T>;
+
+    fn mul(self, rhs: Quantity<V, T>) -> Self::Output {
+        Quantity::new(self.value * rhs.value)
+    }
+}
+
+impl<U, V, T> Div<Quantity<V, T>> for Quantity<U, T>
+where
+    U: Unit + Div<V>,
+    V: Unit,
+    U::Output: Unit,
+    T: Scalar,
+{
+    type Output = Quantity<U::Output, T>;
+
+    fn div(self, rhs: Quantity<V, T>) -> Self::Output {
+        Quantity::new(self.value / rhs.value)
+    }
+}
+
+// 数值部分沿用 T 的格式化参数，例如 {:.2}，后面跟上单位
+impl<U: Unit, T: fmt::Display> fmt::Display for Quantity<U, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)?;
+        write!(f, " {}", U::SUFFIX)
+    }
+}
+
+impl<U: Unit, T: fmt::Debug> fmt::Debug for Quantity<U, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Quantity({:?} {})", self.value, U::SUFFIX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn units_are_zero_sized() {
+        assert_eq!(size_of::<Meter>(), 0);
+        assert_eq!(size_of::<Quantity<Meter, f64>>(), size_of::<f64>());
+        assert_eq!(
+            size_of::<Quantity<KilometerPerHour, u32>>(),
+            size_of::<u32>()
+        );
+    }
+
+    #[test]
+    fn same_dimension_add_and_sub() {
+        // 对应 deep_trait 中的 Millimeters + Meters
+        assert_eq!(
+            Millimeter::of(10u32) + Meter::of(5u32),
+            Millimeter::of(5010)
+        );
+        assert_eq!(Second::of(90) - Minute::of(1), Second::of(30));
+        assert_eq!(
+            Second::of(5) + Minute::of(1) + Hour::of(1),
+            Second::of(3665)
+        );
+        assert_eq!((Hour::of(1i64).to::<Minute>() + Minute::of(30)).value(), 90);
+        assert_eq!(-Meter::of(2.5), Meter::of(-2.5));
+        assert!(Meter::of(1.0) < Meter::of(1.5));
+    }
+
+    #[test]
+    fn mul_and_div_derive_units() {
+        let distance = Kilometer::of(150.0);
+        let time = Hour::of(2.0);
+        let speed = distance / time;
+        assert_eq!(speed, KilometerPerHour::of(75.0));
+        assert_eq!(speed * Hour::of(0.5), Kilometer::of(37.5));
+        assert_eq!(distance / speed, Hour::of(2.0));
+
+        let area = Meter::of(3) * Meter::of(4);
+        assert_eq!(area.to_string(), "12 m²");
+        assert_eq!(area / Meter::of(4), Meter::of(3));
+
+        let acceleration = MeterPerSecond::of(20.0) / Second::of(4.0);
+        let force = Kilogram::of(2.0) * acceleration;
+        assert_eq!(force, Newton::of(10.0));
+        assert_eq!(force / Kilogram::of(2.0), acceleration);
+        assert_eq!(acceleration * Second::of(2.0), MeterPerSecond::of(10.0));
+    }
+
+    #[test]
+    fn scalar_mul_and_div() {
+        assert_eq!(Meter::of(3) * 4, Meter::of(12));
+        assert_eq!(2.0 * Second::of(1.5), Second::of(3.0));
+        assert_eq!(Gram::of(9u64) / 2, Gram::of(4));
+    }
+
+    #[test]
+    fn exact_and_lossy_conversions() {
+        assert_eq!(
+            Kilometer::of(2).to::<Millimeter>(),
+            Millimeter::of(2_000_000)
+        );
+        assert_eq!(Kilogram::of(1.5).to::<Gram>(), Gram::of(1500.0));
+        assert_eq!(Hour::of(2u64).to::<Second>(), Second::of(7200));
+        assert_eq!(Meter::of(7).to::<Meter>(), Meter::of(7));
+
+        // 整数向零截断
+        assert_eq!(Millimeter::of(1999).to_lossy::<Meter>(), Meter::of(1));
+        assert_eq!(Millimeter::of(-1999).to_lossy::<Meter>(), Meter::of(-1));
+        assert_eq!(Millisecond::of(59_999).to_lossy::<Minute>(), Minute::of(0));
+        // km/h 和 m/s 的比例是 5/18，整数之间无法精确换算
+        assert_eq!(
+            KilometerPerHour::of(100).to_lossy::<MeterPerSecond>(),
+            MeterPerSecond::of(27)
+        );
+        assert_eq!(
+            MeterPerSecond::of(10).to_lossy::<KilometerPerHour>(),
+            KilometerPerHour::of(36)
+        );
+
+        let v = KilometerPerHour::of(36.0).to_lossy::<MeterPerSecond>();
+        assert!((v.value() - 10.0f64).abs() < 1e-12);
+        assert_eq!(Gram::of(250.0).to_lossy::<Kilogram>(), Kilogram::of(0.25));
+    }
+
+    #[test]
+    fn integer_conversions_are_checked() {
+        // 3000 km = 3e9 mm，超出 i32 但没有超出 u32
+        assert_eq!(Kilometer::of(3_000i32).checked_to::<Millimeter>(), None);
+        assert_eq!(
+            Kilometer::of(3_000u32).checked_to::<Millimeter>(),
+            Some(Millimeter::of(3_000_000_000))
+        );
+        assert_eq!(Hour::of(u64::MAX).checked_to::<Second>(), None);
+        assert_eq!(
+            Kilometer::of(-3_000i32).checked_to::<Meter>(),
+            Some(Meter::of(-3_000_000))
+        );
+
+        // 先乘后除也不会在中间溢出：结果放得下就能换算
+        assert_eq!(
+            Millisecond::of(i64::MAX).checked_to_lossy::<Hour>(),
+            Some(Hour::of(i64::MAX / 3_600_000))
+        );
+        assert_eq!(
+            KilometerPerHour::of(u32::MAX).to_lossy::<MeterPerSecond>(),
+            MeterPerSecond::of((u64::from(u32::MAX) * 5 / 18) as u32)
+        );
+        assert_eq!(
+            MeterPerSecond::of(u32::MAX).checked_to_lossy::<KilometerPerHour>(),
+            None
+        );
+
+        assert_eq!(Millimeter::of(i32::MAX).checked_add(Meter::of(1)), None);
+        assert_eq!(Second::of(0u32).checked_sub(Minute::of(1)), None);
+        assert_eq!(
+            Second::of(90u32).checked_sub(Minute::of(1)),
+            Some(Second::of(30))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "to: km -> mm overflows the scalar type")]
+    fn to_panics_instead_of_wrapping() {
+        let _ = Kilometer::of(3_000i32).to::<Millimeter>();
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when adding quantities")]
+    fn mixed_unit_add_panics_on_overflow() {
+        let _ = Millimeter::of(0i32) + Kilometer::of(3_000);
+    }
+
+    #[test]
+    fn display_and_debug_print_the_unit() {
+        assert_eq!(Meter::of(5).to_string(), "5 m");
+        assert_eq!(
+            format!("{:.2}", KilometerPerHour::of(12.3456)),
+            "12.35 km/h"
+        );
+        assert_eq!(format!("{:>4}", Second::of(7)), "   7 s");
+        assert_eq!(format!("{:?}", Newton::of(1.5)), "Quantity(1.5 N)");
+        assert_eq!(
+            Quantity::<MeterPerSecondSquared>::default().to_string(),
+            "0 m/s²"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// 各章节中被注释掉的 "下面的代码将报错" 片段都被提取到了 tests/compile_fail 目录下，
+// 每个片段用 `// error: ...` 注释声明期望的错误：以 E 开头的是错误码(例如 E0502)，其它的按报错信息的子串匹配。
+// 这里逐个调用本地的 rustc 编译这些片段，确认它们确实编译失败，并且失败的原因与注释中说的一致。
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/compile_fail");
+    let mut fixtures: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .collect();
+    fixtures.sort();
+    fixtures
+}
+
+fn expected_errors(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .filter_map(|line| line.strip_prefix("// error:"))
+        .map(|e| e.trim().to_string())
+        .collect()
+}
+
+// 返回 rustc 的报错输出；如果片段竟然编译通过了，返回 None
+fn compile(fixture: &Path, out_dir: &Path) -> Option<String> {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = Command::new(rustc)
+        .args([
+            "--edition",
+            "2021",
+            "--crate-type",
+            "bin",
+            "--emit=metadata",
+        ])
+        .arg("--out-dir")
+        .arg(out_dir)
+        .arg(fixture)
+        .output()
+        .expect("failed to run rustc");
+    if output.status.success() {
+        None
+    } else {
+        Some(String::from_utf8_lossy(&output.stderr).into_owned())
+    }
+}
+
+fn check(fixture: &Path, out_dir: &Path) -> Result<(), String> {
+    let source = fs::read_to_string(fixture).unwrap();
+    let expected = expected_errors(&source);
+    if expected.is_empty() {
+        return Err("no `// error:` annotation".to_string());
+    }
+
+    let stderr = compile(fixture, out_dir).ok_or("compiled successfully")?;
+    let missing: Vec<&String> = expected
+        .iter()
+        .filter(|e| {
+            if e.starts_with('E') && e[1..].chars().all(|c| c.is_ascii_digit()) {
+                !stderr.contains(&format!("error[{}]", e))
+            } else {
+                !stderr.contains(e.as_str())
+            }
+        })
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("expected {:?}, rustc said:\n{}", missing, stderr))
+    }
+}
+
+#[test]
+fn commented_out_snippets_fail_to_compile() {
+    let out_dir =
+        env::temp_dir().join(format!("rust_thinking_compile_fail_{}", std::process::id()));
+    fs::create_dir_all(&out_dir).unwrap();
+
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty());
+
+    let failures: Vec<String> = fixtures
+        .iter()
+        .filter_map(|fixture| {
+            check(fixture, &out_dir)
+                .err()
+                .map(|e| format!("{}: {}", fixture.display(), e))
+        })
+        .collect();
+
+    fs::remove_dir_all(&out_dir).unwrap();
+    assert!(failures.is_empty(), "\n{}", failures.join("\n\n"));
+}
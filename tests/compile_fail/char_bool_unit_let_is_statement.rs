@@ -0,0 +1,7 @@
+// 来源: src/char_bool_unit.rs
+// let 是语句，不能将 let 语句赋值给其它值
+// error: expected expression, found `let` statement
+
+fn main() {
+    let b = (let a = 8);
+}
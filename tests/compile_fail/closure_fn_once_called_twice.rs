@@ -0,0 +1,16 @@
+// 来源: src/closure.rs
+// 仅实现 FnOnce 特征的闭包在调用时会转移所有权，不能对已失去所有权的闭包变量进行二次调用
+// error: E0382
+
+fn fn_once<F>(func: F)
+where
+    F: FnOnce(usize) -> bool,
+{
+    println!("{}", func(3));
+    println!("{}", func(4));
+}
+
+fn main() {
+    let x = vec![1, 2, 3];
+    fn_once(|z| z == x.len());
+}
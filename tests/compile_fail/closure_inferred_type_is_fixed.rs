@@ -0,0 +1,9 @@
+// 来源: src/closure.rs
+// 闭包的类型推导不是泛型，第一次调用推导出 String 后，再用整数调用就会报错
+// error: E0308
+
+fn main() {
+    let example_closure = |x| x;
+    let s = example_closure(String::from("hello"));
+    let n = example_closure(5);
+}
@@ -0,0 +1,16 @@
+// 来源: src/closure.rs
+// 把捕获的 s 移出闭包后，该闭包就只实现了 FnOnce，不满足 FnMut 的约束
+// error: E0525
+
+fn exec_move<'a, F: FnMut(&'a str) -> String>(mut f: F) {
+    f("hello");
+}
+
+fn main() {
+    let mut s = String::new();
+    let update_string = |str| -> String {
+        s.push_str(str);
+        s
+    };
+    exec_move(update_string);
+}
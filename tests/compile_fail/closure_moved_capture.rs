@@ -0,0 +1,16 @@
+// 来源: src/closure.rs
+// fn_once_1 的闭包把 x 的所有权转移了出去，之后 x 不能再使用
+// error: E0382
+
+fn fn_once_1<F>(func: F)
+where
+    F: FnOnce() -> Vec<i32>,
+{
+    println!("{:?}", func());
+}
+
+fn main() {
+    let x = vec![1, 2, 3];
+    fn_once_1(|| x);
+    println!("{:?}", x);
+}
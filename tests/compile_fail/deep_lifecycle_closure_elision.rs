@@ -0,0 +1,7 @@
+// 来源: src/deep_lifecycle.rs
+// 闭包不适用函数的生命周期消除规则，输入和输出的生命周期被推导成了两个不同的生命周期
+// error: lifetime may not live long enough
+
+fn main() {
+    let closure_slision = |x: &i32| -> &i32 { x };
+}
@@ -0,0 +1,15 @@
+// 来源: src/fn_combinators.rs
+// once 的测试中 consume 闭包把捕获的 v 移出，只实现了 FnOnce，不能当作 Fn 使用，所以才需要 once 把它包装成 Fn
+// error: E0525
+
+fn is_fn<A, R>(_: &impl Fn(A) -> R) {}
+
+fn main() {
+    let v = vec![1, 2, 3];
+    let consume = move |extra: i32| {
+        let mut v = v;
+        v.push(extra);
+        v
+    };
+    is_fn(&consume);
+}
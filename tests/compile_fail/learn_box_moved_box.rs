@@ -0,0 +1,10 @@
+// 来源: src/learn_box.rs
+// arr 不再拥有堆上数组的所有权，因此不能再使用
+// error: E0382
+
+fn main() {
+    let arr = Box::new([0; 1000]);
+    let arr1 = arr;
+    println!("{:?}", arr1.len());
+    println!("{:?}", arr.len());
+}
@@ -0,0 +1,26 @@
+// 来源: src/learn_deref.rs
+// *s 会尝试把 String 移出智能指针，而 String 没有实现 Copy 特征
+// error: E0507
+
+use std::ops::Deref;
+
+struct MyBox<T>(T);
+
+impl<T> MyBox<T> {
+    fn new(x: T) -> MyBox<T> {
+        MyBox(x)
+    }
+}
+
+impl<T> Deref for MyBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+fn main() {
+    let s = MyBox::new(String::from("hello world"));
+    let mys = *s;
+}
@@ -0,0 +1,16 @@
+// 来源: src/learn_drop.rs
+// Copy 和 Drop 是互斥的，实现了 Copy 的类型不能再拥有析构函数
+// error: E0184
+
+#[derive(Clone, Copy)]
+struct Foo;
+
+impl Drop for Foo {
+    fn drop(&mut self) {
+        println!("Dropping Foo!")
+    }
+}
+
+fn main() {
+    let _foo = Foo;
+}
@@ -0,0 +1,16 @@
+// 来源: src/learn_drop.rs
+// 不允许显式调用析构函数 Drop::drop，需要使用 std::mem::drop
+// error: E0040
+
+struct Foo;
+
+impl Drop for Foo {
+    fn drop(&mut self) {
+        println!("Dropping Foo!")
+    }
+}
+
+fn main() {
+    let foo = Foo;
+    foo.drop();
+}
@@ -0,0 +1,18 @@
+// 来源: src/learn_drop.rs
+// drop 函数拿走了 foo 的所有权，之后再使用 foo 就是借用了所有权已经被转移的值
+// error: E0382
+
+#[derive(Debug)]
+struct Foo;
+
+impl Drop for Foo {
+    fn drop(&mut self) {
+        println!("Dropping Foo!")
+    }
+}
+
+fn main() {
+    let foo = Foo;
+    drop(foo);
+    println!("Running!:{:?}", foo);
+}
@@ -0,0 +1,11 @@
+// 来源: src/learn_iterator.rs
+// 下面的代码将报错，因为 values 的所有权在 `for` 循环中已经被转移走
+// error: E0382
+
+fn main() {
+    let values = vec![1, 2, 3];
+    for v in values.into_iter() {
+        println!("{}", v)
+    }
+    println!("{:?}", values);
+}
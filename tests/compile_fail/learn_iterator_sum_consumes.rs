@@ -0,0 +1,10 @@
+// 来源: src/learn_iterator.rs
+// `sum` 拿到了迭代器 `v1_iter` 的所有权，之后不能再使用 v1_iter
+// error: E0382
+
+fn main() {
+    let v1 = vec![1, 2, 3];
+    let v1_iter = v1.iter();
+    let total: i32 = v1_iter.sum();
+    println!("{:?}", v1_iter);
+}
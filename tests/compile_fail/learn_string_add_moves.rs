@@ -0,0 +1,10 @@
+// 来源: src/learn_string.rs
+// add() 的定义是 fn add(self, s: &str) -> String，s1 的所有权被转移走了，后面不能再使用 s1
+// error: E0382
+
+fn main() {
+    let s1 = String::from("hello,");
+    let s2 = String::from("world!");
+    let s3 = s1 + &s2;
+    println!("{}", s1);
+}
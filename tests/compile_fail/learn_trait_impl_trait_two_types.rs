@@ -0,0 +1,43 @@
+// 来源: src/learn_trait.rs
+// 返回 impl Trait 时只能有一个具体类型，if 和 else 分支返回了不同的类型
+// error: E0308
+
+trait Summary {
+    fn summarize(&self) -> String;
+}
+
+struct Post {
+    title: String,
+}
+
+impl Summary for Post {
+    fn summarize(&self) -> String {
+        self.title.clone()
+    }
+}
+
+struct Weibo {
+    username: String,
+}
+
+impl Summary for Weibo {
+    fn summarize(&self) -> String {
+        self.username.clone()
+    }
+}
+
+fn returns_summarizable(switch: bool) -> impl Summary {
+    if switch {
+        Post {
+            title: String::from("Penguins win the Stanley Cup Championship!"),
+        }
+    } else {
+        Weibo {
+            username: String::from("horse_ebooks"),
+        }
+    }
+}
+
+fn main() {
+    println!("{}", returns_summarizable(true).summarize());
+}
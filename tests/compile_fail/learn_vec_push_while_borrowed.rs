@@ -0,0 +1,10 @@
+// 来源: src/learn_vec.rs
+// 数组的第一个元素被不可变借用后，又通过 push 可变借用了整个数组，first 可能指向已经被重新分配的内存
+// error: E0502
+
+fn main() {
+    let mut v = vec![1, 2, 3, 4, 5];
+    let first = &v[0];
+    v.push(6);
+    println!("The first element is: {}", first);
+}
@@ -0,0 +1,11 @@
+// 来源: src/newtype_and_type_aliases.rs
+// newtype 不会继承内部类型的方法，MilliMeters 上没有 u32 的 pow 方法
+// error: E0599
+
+struct MilliMeters(u32);
+
+fn main() {
+    let i: u32 = 2;
+    let n = MilliMeters(i);
+    assert_eq!(n.pow(2), 4);
+}
@@ -0,0 +1,9 @@
+// 来源: src/rc_arc.rs
+// s 已经被转移给 a，再尝试将 s 转移给 b 会报错
+// error: E0382
+
+fn main() {
+    let s = String::from("hello, world");
+    let a = Box::new(s);
+    let b = Box::new(s);
+}
@@ -0,0 +1,14 @@
+// 来源: src/rc_arc.rs
+// Rc<T> 没有实现 Send 特征，不能在线程间传递
+// error: E0277
+
+use std::rc::Rc;
+use std::thread;
+
+fn main() {
+    let s = Rc::new(String::from("多线程漫游者"));
+    for _ in 0..10 {
+        let s = Rc::clone(&s);
+        let handle = thread::spawn(move || println!("{}", s));
+    }
+}
@@ -0,0 +1,11 @@
+// 来源: src/refcell.rs
+// Cell::get 要求 T: Copy，String 没有实现 Copy，所以 Cell<String> 上不能调用 get
+// error: E0599
+
+use std::cell::Cell;
+
+fn main() {
+    let c = Cell::new(String::from("asdf"));
+    let one = c.get();
+    println!("{}", one);
+}
@@ -0,0 +1,9 @@
+// 来源: src/refcell.rs
+// 不能对一个不可变的值进行可变借用
+// error: E0596
+
+fn main() {
+    let x = 5;
+    let y = &mut x;
+    *y += 1;
+}
@@ -0,0 +1,12 @@
+// 来源: src/refcell.rs
+// 不借助 Cell 时，同一时刻不能存在两个可变借用，代码 2 无法通过编译
+// error: E0499
+
+fn main() {
+    let mut x = 1;
+    let y = &mut x;
+    let z = &mut x;
+    *y = 3;
+    *z = 4;
+    println!("{}", x);
+}
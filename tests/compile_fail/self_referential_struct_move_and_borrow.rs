@@ -0,0 +1,16 @@
+// 来源: src/self_referential_struct.rs
+// 同时使用值和值的引用，所有权转移和借用一起发生了
+// error: E0382
+
+struct SelfRef<'a> {
+    value: String,
+    pointer_to_value: &'a str,
+}
+
+fn main() {
+    let s = "aaa".to_string();
+    let v = SelfRef {
+        value: s,
+        pointer_to_value: &s,
+    };
+}
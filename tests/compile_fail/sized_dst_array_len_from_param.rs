@@ -0,0 +1,11 @@
+// 来源: src/sized_dst.rs
+// 数组长度是类型的一部分，必须在编译期可知，不能来自运行期的参数
+// error: E0435
+
+fn my_function(n: usize) {
+    let array = [123; n];
+}
+
+fn main() {
+    my_function(3);
+}
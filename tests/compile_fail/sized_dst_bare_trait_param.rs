@@ -0,0 +1,9 @@
+// 来源: src/sized_dst.rs
+// 特征本身是 DST，不能直接作为参数类型，必须通过 &dyn 或者 Box<dyn> 来使用
+// error: E0782
+
+trait MyThing {}
+
+fn foobar_3(thing: MyThing) {}
+
+fn main() {}
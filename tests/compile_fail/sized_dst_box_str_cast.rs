@@ -0,0 +1,7 @@
+// 来源: src/sized_dst.rs
+// 不能主动把 &str 转换成 str 再装箱，只能让编译器通过 into 来完成
+// error: E0620
+
+fn main() {
+    let s1: Box<str> = Box::new("Hello there!" as str);
+}
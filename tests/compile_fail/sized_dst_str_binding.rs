@@ -0,0 +1,8 @@
+// 来源: src/sized_dst.rs
+// str 是动态大小类型，无法直接用作变量的类型
+// error: E0308
+// error: E0277
+
+fn main() {
+    let s1: str = "Hello there!";
+}
@@ -0,0 +1,7 @@
+// 来源: src/sized_dst.rs
+// [u8] 同样是 DST，不能直接声明这种类型的局部变量
+// error: E0277
+
+fn main() {
+    let a: [u8];
+}
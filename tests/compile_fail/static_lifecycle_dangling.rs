@@ -0,0 +1,13 @@
+// 来源: src/static_lifecycle.rs
+// s1 是 String 类型，在内部作用域结束时就被 drop，r3 不能继续引用它
+// error: E0597
+
+fn main() {
+    let r3: &str;
+
+    {
+        let s1 = "String".to_string();
+        r3 = &s1;
+    }
+    println!("{}", r3);
+}
@@ -0,0 +1,14 @@
+// 来源: src/static_lifecycle.rs
+// &i 的生命周期无法满足 'static 的约束
+// error: E0597
+
+use std::fmt::Debug;
+
+fn print_it<T: Debug + 'static>(input: T) {
+    println!("'static value passed in is: {:?}", input);
+}
+
+fn main() {
+    let i = 5;
+    print_it(&i);
+}
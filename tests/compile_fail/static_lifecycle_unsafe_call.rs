@@ -0,0 +1,13 @@
+// 来源: src/static_lifecycle.rs
+// get_str_at_location 是 unsafe 函数，必须在 unsafe 块中调用
+// error: E0133
+
+use std::{slice::from_raw_parts, str::from_utf8_unchecked};
+
+unsafe fn get_str_at_location(pointer: usize, length: usize) -> &'static str {
+    unsafe { from_utf8_unchecked(from_raw_parts(pointer as *const u8, length)) }
+}
+
+fn main() {
+    let message = get_str_at_location(1000, 10);
+}